@@ -0,0 +1,22 @@
+//! Language server for MagicQ `.shw` files, speaking LSP over stdio.
+//!
+//! Enabled by the `lsp` feature; without it the binary is a stub that tells you
+//! how to turn it on.
+
+#[cfg(feature = "lsp")]
+#[tokio::main]
+async fn main() {
+    use magicq::lsp::Backend;
+    use tower_lsp::{LspService, Server};
+
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+    let (service, socket) = LspService::new(Backend::new);
+    Server::new(stdin, stdout, socket).serve(service).await;
+}
+
+#[cfg(not(feature = "lsp"))]
+fn main() {
+    eprintln!("rebuild with `--features lsp` to enable the language server");
+    std::process::exit(1);
+}
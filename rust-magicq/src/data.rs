@@ -1,7 +1,33 @@
-pub trait SectionData {
-    const IDENTIFIER: &'static str;
-    fn from_section(section: &Section) -> Result<Self, String> where Self: Sized;
-    fn to_section(&self) -> Section;
-}
-
-include!("data/version.rs");
\ No newline at end of file
+use crate::showfile::Section;
+
+mod de;
+mod error;
+mod hex;
+mod sections;
+mod ser;
+
+pub use de::{from_row, from_section, RowDeserializer, SectionDeserializer, ValueDeserializer};
+pub use error::Error;
+pub use hex::Hex;
+pub use sections::{
+    CueStack, ExecuteItem, ExecutePage, Fixture, Fx, Group, Head, Palette, Playback, Settings,
+    TypedSection, Version, VersionRecord,
+};
+pub use ser::{to_row, to_section};
+
+/// A typed wrapper around a [`Section`] for a known [`SectionIdentifier`].
+///
+/// Every wrapper keeps the parsed [`Section`] verbatim, so `from_section` ->
+/// `to_section` is byte-identical by construction — unknown and trailing
+/// columns survive the MagicQ schema drift between versions. On top of that a
+/// wrapper exposes named accessors (e.g. [`name`](sections::Settings::name))
+/// instead of positional `section[r][c]` indexing, and the one section with a
+/// documented layout, [`Version`](sections::Version), decodes into fully named
+/// fields through the data-format in [`de`]/[`ser`].
+pub trait SectionData {
+    const IDENTIFIER: &'static str;
+    fn from_section(section: &Section) -> Result<Self, Error>
+    where
+        Self: Sized;
+    fn to_section(&self) -> Section;
+}
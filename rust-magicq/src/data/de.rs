@@ -0,0 +1,359 @@
+use serde::de::{
+    DeserializeSeed, Deserializer, EnumAccess, IntoDeserializer, SeqAccess, VariantAccess,
+    Visitor,
+};
+use serde::Deserialize;
+
+use crate::showfile::{Row, Section, Value};
+
+use super::error::Error;
+use super::hex;
+
+/// Decode a typed section from the first row of `section`.
+///
+/// Most MagicQ sections carry a single logical record per [`Section`]; the
+/// typed struct is deserialized from `section[0]`. Sections that hold a list of
+/// records (a palette, a group) deserialize as a sequence with [`from_section`].
+pub fn from_row<'de, T>(row: &'de Row) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(RowDeserializer::new(row))
+}
+
+/// Decode a typed section that is a sequence of per-row records.
+pub fn from_section<'de, T>(section: &'de Section) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(SectionDeserializer::new(section))
+}
+
+/// Deserializer treating a [`Section`] as a sequence of [`Row`]s.
+pub struct SectionDeserializer<'de> {
+    section: &'de Section,
+}
+
+impl<'de> SectionDeserializer<'de> {
+    pub fn new(section: &'de Section) -> Self {
+        Self { section }
+    }
+}
+
+impl<'de> Deserializer<'de> for SectionDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Rows {
+            iter: self.section.into_iter(),
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct Rows<'de> {
+    iter: std::slice::Iter<'de, Row>,
+}
+
+impl<'de> SeqAccess<'de> for Rows<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(row) => seed.deserialize(RowDeserializer::new(row)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Deserializer treating a [`Row`] as a sequence of fields.
+pub struct RowDeserializer<'de> {
+    row: &'de Row,
+}
+
+impl<'de> RowDeserializer<'de> {
+    pub fn new(row: &'de Row) -> Self {
+        Self { row }
+    }
+}
+
+impl<'de> Deserializer<'de> for RowDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Fields {
+            iter: self.row.into_iter(),
+        })
+    }
+
+    // A struct maps its fields positionally onto the row's columns, just like a
+    // tuple does — serde names don't appear in the on-disk format.
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct Fields<'de> {
+    iter: std::slice::Iter<'de, Value>,
+}
+
+impl<'de> SeqAccess<'de> for Fields<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(ValueDeserializer::new(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Deserializer mapping a single [`Value`] onto the requested Rust type.
+pub struct ValueDeserializer<'de> {
+    value: &'de Value,
+}
+
+impl<'de> ValueDeserializer<'de> {
+    pub fn new(value: &'de Value) -> Self {
+        Self { value }
+    }
+
+    fn hex(&self, expected: Option<usize>) -> Result<u64, Error> {
+        match self.value {
+            Value::Hex(v, w) => match expected {
+                Some(e) if *w != e => Err(Error::HexWidth { expected: e, found: *w }),
+                _ => Ok(*v),
+            },
+            other => Err(Error::UnexpectedValue {
+                expected: "hex value",
+                found: format!("{:?}", other),
+            }),
+        }
+    }
+
+    fn float(&self) -> Result<f64, Error> {
+        match self.value {
+            Value::Float(f) => Ok(*f),
+            other => Err(Error::UnexpectedValue {
+                expected: "float value",
+                found: format!("{:?}", other),
+            }),
+        }
+    }
+
+    fn string(&self) -> Result<&'de str, Error> {
+        match self.value {
+            Value::String(s) => Ok(s.as_str()),
+            other => Err(Error::UnexpectedValue {
+                expected: "string value",
+                found: format!("{:?}", other),
+            }),
+        }
+    }
+}
+
+macro_rules! deserialize_int {
+    ($method:ident, $visit:ident, $ty:ty) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.$visit(self.hex(None)? as $ty)
+        }
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Float(f) => visitor.visit_f64(*f),
+            Value::String(s) => visitor.visit_borrowed_str(s),
+            Value::Hex(v, _) => visitor.visit_u64(*v),
+        }
+    }
+
+    deserialize_int!(deserialize_i8, visit_i8, i8);
+    deserialize_int!(deserialize_i16, visit_i16, i16);
+    deserialize_int!(deserialize_i32, visit_i32, i32);
+    deserialize_int!(deserialize_i64, visit_i64, i64);
+    deserialize_int!(deserialize_u8, visit_u8, u8);
+    deserialize_int!(deserialize_u16, visit_u16, u16);
+    deserialize_int!(deserialize_u32, visit_u32, u32);
+    deserialize_int!(deserialize_u64, visit_u64, u64);
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f32(self.float()? as f32)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(self.float()?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    // An empty string is MagicQ's spelling of "no value"; everything else is
+    // present.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::String(s) if s.is_empty() => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    // The hex width rides in on the newtype-struct name emitted by `Hex<W>`.
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if let Some(width) = hex::width_of(name) {
+            let v = self.hex(Some(width))?;
+            visitor.visit_u64(v)
+        } else {
+            visitor.visit_newtype_struct(self)
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(Enum { de: self })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i128 u128 char bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct Enum<'de> {
+    de: ValueDeserializer<'de>,
+}
+
+impl<'de> EnumAccess<'de> for Enum<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let tag = self.de.string()?;
+        let value = seed.deserialize(tag.into_deserializer())?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Enum<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.de.deserialize_any(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.de.deserialize_any(visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::data::{from_row, to_row, Hex};
+    use crate::showfile::{Row, Value};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        id: Hex<4>,
+        name: String,
+        level: Hex<2>,
+        note: Option<String>,
+    }
+
+    #[test]
+    fn round_trips_a_record_through_row() {
+        let record = Record {
+            id: Hex(0x7d),
+            name: "Front wash".to_string(),
+            level: Hex(0xff),
+            note: Some("hi".to_string()),
+        };
+
+        let row = to_row(&record).expect("serialize");
+        // The hex widths ride on the `Hex<W>` types, not the runtime value.
+        assert!(matches!(row.get_values()[0], Value::Hex(0x7d, 4)));
+        assert!(matches!(row.get_values()[2], Value::Hex(0xff, 2)));
+
+        let decoded: Record = from_row(&row).expect("deserialize");
+        assert_eq!(decoded, record);
+    }
+
+    #[test]
+    fn empty_string_decodes_to_none() {
+        let row = Row::from_values(vec![
+            Value::Hex(1, 4),
+            Value::String("x".to_string()),
+            Value::Hex(0, 2),
+            Value::String(String::new()),
+        ]);
+        let decoded: Record = from_row(&row).expect("deserialize");
+        assert_eq!(decoded.note, None);
+    }
+
+    #[test]
+    fn wrong_hex_width_is_rejected() {
+        let row = Row::from_values(vec![
+            Value::Hex(1, 2), // expected width 4
+            Value::String("x".to_string()),
+            Value::Hex(0, 2),
+            Value::String(String::new()),
+        ]);
+        assert!(from_row::<Record>(&row).is_err());
+    }
+}
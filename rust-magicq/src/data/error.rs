@@ -0,0 +1,46 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::{de, ser};
+
+/// Error produced while (de)serializing a typed section through the
+/// [`SectionData`](super::SectionData) layer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A free-form message, used for the `serde` `custom` constructors.
+    Message(String),
+    /// A `Value` of the wrong shape was encountered for the requested type.
+    UnexpectedValue { expected: &'static str, found: String },
+    /// A `Value::Hex` was the right variant but the wrong field width.
+    HexWidth { expected: usize, found: usize },
+    /// A row ran out of fields before the struct was filled.
+    Eof,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Message(msg) => f.write_str(msg),
+            Error::UnexpectedValue { expected, found } => {
+                write!(f, "expected {}, got {} instead", expected, found)
+            }
+            Error::HexWidth { expected, found } => {
+                write!(f, "hex value is {} characters long instead of {}", found, expected)
+            }
+            Error::Eof => f.write_str("unexpected end of row"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
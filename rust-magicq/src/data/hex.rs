@@ -0,0 +1,82 @@
+use serde::{
+    de::{self, Deserialize, Deserializer, Visitor},
+    ser::{Serialize, Serializer},
+};
+use std::fmt;
+
+/// A hex field of a fixed character width `W`.
+///
+/// MagicQ writes each hex column zero-padded to a fixed width (and, for the
+/// 16-wide columns, in upper case — see [`Value::Display`](crate::showfile::Value)).
+/// That width is part of the on-disk layout, not something that can be inferred
+/// from the value, so a typed section carries it in the type: a `value0: Hex<4>`
+/// round-trips as four hex digits no matter how small the number is.
+///
+/// The width travels to the data-format serializer through the newtype-struct
+/// name (see [`token`]), which is how the serde layer recovers it without a
+/// side channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Hex<const W: usize>(pub u64);
+
+/// Prefix of the newtype-struct name used to smuggle the hex width through serde.
+pub(crate) const TOKEN_PREFIX: &str = "$hex:";
+
+/// The newtype-struct name carrying width `W`, e.g. `"$hex:4"`.
+///
+/// Only the widths MagicQ actually emits are covered; an unknown width is a
+/// programming error in a typed-section definition, so it panics rather than
+/// silently losing the padding.
+const fn token<const W: usize>() -> &'static str {
+    match W {
+        2 => "$hex:2",
+        4 => "$hex:4",
+        6 => "$hex:6",
+        8 => "$hex:8",
+        16 => "$hex:16",
+        _ => panic!("unsupported Hex width"),
+    }
+}
+
+/// Parse the width back out of a [`token`]-shaped newtype-struct name.
+pub(crate) fn width_of(name: &str) -> Option<usize> {
+    name.strip_prefix(TOKEN_PREFIX).and_then(|w| w.parse().ok())
+}
+
+impl<const W: usize> Serialize for Hex<W> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(token::<W>(), &self.0)
+    }
+}
+
+impl<'de, const W: usize> Deserialize<'de> for Hex<W> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct HexVisitor<const W: usize>;
+
+        impl<'de, const W: usize> Visitor<'de> for HexVisitor<W> {
+            type Value = Hex<W>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a {}-wide hex field", W)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(Hex(v))
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_u64(self)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(token::<W>(), HexVisitor::<W>)
+    }
+}
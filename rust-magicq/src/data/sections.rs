@@ -0,0 +1,375 @@
+use serde::{Deserialize, Serialize};
+
+use crate::showfile::{Row, Section, SectionIdentifier, Showfile, Value};
+
+use super::error::Error;
+use super::hex::Hex;
+use super::{from_row, SectionData};
+
+/// Declare a typed section wrapper for a known [`SectionIdentifier`].
+///
+/// **Reduced scope, by design.** Only the `V` section has a column layout
+/// documented well enough to decode into named fields (see [`Version`]); the
+/// per-column meanings of the other sections — cue numbers, fade times,
+/// fixture heads, and so on — are undocumented and drift between MagicQ
+/// versions. Rather than guess at field positions (and silently drop the
+/// columns a newer export adds), each wrapper keeps the parsed [`Section`]
+/// verbatim and exposes only the generic [`name`](Settings::name) accessor plus
+/// [`rows`](Settings::rows). That makes `from_section` -> `to_section`
+/// byte-identical by construction and gives callers a named type to dispatch
+/// on; richer accessors can be filled in per section as each layout is pinned
+/// down.
+macro_rules! typed_section {
+    ($(#[$doc:meta])* $name:ident, $ident:ident, $code:literal) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            section: Section,
+        }
+
+        impl $name {
+            /// The underlying section, preserved exactly as parsed.
+            pub fn section(&self) -> &Section {
+                &self.section
+            }
+
+            /// The rows of this section.
+            pub fn rows(&self) -> &[Row] {
+                self.section.get_rows()
+            }
+
+            /// The section's name: the first quoted field of its first row.
+            ///
+            /// Every MagicQ section carries a human-readable name in a string
+            /// column; this resolves it without the caller hard-coding the
+            /// `section[0][1]` index. `None` when the section is empty or
+            /// carries no string field (a schema the registry does not model).
+            pub fn name(&self) -> Option<&str> {
+                self.section
+                    .get_rows()
+                    .first()?
+                    .get_values()
+                    .iter()
+                    .find_map(Value::as_str)
+            }
+        }
+
+        impl SectionData for $name {
+            const IDENTIFIER: &'static str = $code;
+
+            fn from_section(section: &Section) -> Result<Self, Error> {
+                match section.get_identifier() {
+                    SectionIdentifier::$ident => Ok(Self { section: section.clone() }),
+                    other => Err(Error::UnexpectedValue {
+                        expected: concat!("section `", $code, "`"),
+                        found: other.to_code().to_string(),
+                    }),
+                }
+            }
+
+            fn to_section(&self) -> Section {
+                self.section.clone()
+            }
+        }
+    };
+}
+
+typed_section!(
+    /// `T` — global show settings.
+    Settings, Settings, "T");
+typed_section!(
+    /// `P` — a fixture head (patch) definition.
+    Head, Head, "P");
+typed_section!(
+    /// `L` — a patched fixture.
+    Fixture, Fixture, "L");
+typed_section!(
+    /// `F` — a palette entry.
+    Palette, Palette, "F");
+typed_section!(
+    /// `G` — a fixture group.
+    Group, Group, "G");
+typed_section!(
+    /// `W` — an FX (effect) definition.
+    Fx, FX, "W");
+typed_section!(
+    /// `S` — a playback.
+    Playback, Playback, "S");
+typed_section!(
+    /// `C` — a cue stack.
+    CueStack, CueStack, "C");
+typed_section!(
+    /// `M` — an execute page.
+    ExecutePage, ExecutePage, "M");
+typed_section!(
+    /// `N` — an execute item.
+    ExecuteItem, ExecuteItem, "N");
+
+/// `V` — the showfile version record.
+///
+/// Unlike the other sections, the `V` layout is documented
+/// (`V,007d,"MagicQ 1",01090307,0000,0002,`), so it decodes into fully named
+/// fields via [`decode`](Self::decode). The raw [`Section`] is still kept so
+/// [`to_section`](SectionData::to_section) round-trips byte-for-byte.
+#[derive(Debug, Clone)]
+pub struct Version {
+    section: Section,
+}
+
+impl Version {
+    /// The underlying section, preserved exactly as parsed.
+    pub fn section(&self) -> &Section {
+        &self.section
+    }
+
+    /// Decode the named fields of the version record through the serde
+    /// data-format, recovering the hex column widths via [`Hex<W>`].
+    pub fn decode(&self) -> Result<VersionRecord, Error> {
+        match self.section.get_rows().first() {
+            Some(row) => from_row(row),
+            None => Err(Error::Eof),
+        }
+    }
+}
+
+impl SectionData for Version {
+    const IDENTIFIER: &'static str = "V";
+
+    fn from_section(section: &Section) -> Result<Self, Error> {
+        match section.get_identifier() {
+            SectionIdentifier::Version => Ok(Self { section: section.clone() }),
+            other => Err(Error::UnexpectedValue {
+                expected: "section `V`",
+                found: other.to_code().to_string(),
+            }),
+        }
+    }
+
+    fn to_section(&self) -> Section {
+        self.section.clone()
+    }
+}
+
+/// The named fields of a [`Version`] section, e.g.
+/// `V,007d,"MagicQ 1",01090307,0000,0002,`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionRecord {
+    pub value0: Hex<4>,
+    pub name: String,
+    pub value1: Hex<8>,
+    pub value2: Hex<4>,
+    pub value3: Hex<4>,
+}
+
+/// A section decoded into its typed variant, falling back to the raw
+/// [`Section`] for identifiers the registry does not (yet) model.
+#[derive(Debug, Clone)]
+pub enum TypedSection {
+    Version(Version),
+    Settings(Settings),
+    Head(Head),
+    Fixture(Fixture),
+    Palette(Palette),
+    Group(Group),
+    Fx(Fx),
+    Playback(Playback),
+    CueStack(CueStack),
+    ExecutePage(ExecutePage),
+    ExecuteItem(ExecuteItem),
+    /// Any unknown identifier is kept verbatim so re-serialization of a
+    /// partially-understood show is byte-identical.
+    Raw(Section),
+}
+
+impl TypedSection {
+    fn decode(section: &Section) -> TypedSection {
+        // A registered wrapper only ever rejects a mismatched identifier, which
+        // the match below already rules out, so the `expect`s are unreachable.
+        match section.get_identifier() {
+            SectionIdentifier::Version => {
+                TypedSection::Version(Version::from_section(section).expect("identifier matches"))
+            }
+            SectionIdentifier::Settings => {
+                TypedSection::Settings(Settings::from_section(section).expect("identifier matches"))
+            }
+            SectionIdentifier::Head => {
+                TypedSection::Head(Head::from_section(section).expect("identifier matches"))
+            }
+            SectionIdentifier::Fixture => {
+                TypedSection::Fixture(Fixture::from_section(section).expect("identifier matches"))
+            }
+            SectionIdentifier::Palette => {
+                TypedSection::Palette(Palette::from_section(section).expect("identifier matches"))
+            }
+            SectionIdentifier::Group => {
+                TypedSection::Group(Group::from_section(section).expect("identifier matches"))
+            }
+            SectionIdentifier::FX => {
+                TypedSection::Fx(Fx::from_section(section).expect("identifier matches"))
+            }
+            SectionIdentifier::Playback => {
+                TypedSection::Playback(Playback::from_section(section).expect("identifier matches"))
+            }
+            SectionIdentifier::CueStack => {
+                TypedSection::CueStack(CueStack::from_section(section).expect("identifier matches"))
+            }
+            SectionIdentifier::ExecutePage => TypedSection::ExecutePage(
+                ExecutePage::from_section(section).expect("identifier matches"),
+            ),
+            SectionIdentifier::ExecuteItem => TypedSection::ExecuteItem(
+                ExecuteItem::from_section(section).expect("identifier matches"),
+            ),
+            SectionIdentifier::Unknown(_) => TypedSection::Raw(section.clone()),
+        }
+    }
+
+    /// Re-encode this typed section back to a [`Section`], byte-for-byte.
+    pub fn to_section(&self) -> Section {
+        match self {
+            TypedSection::Version(s) => s.to_section(),
+            TypedSection::Settings(s) => s.to_section(),
+            TypedSection::Head(s) => s.to_section(),
+            TypedSection::Fixture(s) => s.to_section(),
+            TypedSection::Palette(s) => s.to_section(),
+            TypedSection::Group(s) => s.to_section(),
+            TypedSection::Fx(s) => s.to_section(),
+            TypedSection::Playback(s) => s.to_section(),
+            TypedSection::CueStack(s) => s.to_section(),
+            TypedSection::ExecutePage(s) => s.to_section(),
+            TypedSection::ExecuteItem(s) => s.to_section(),
+            TypedSection::Raw(s) => s.clone(),
+        }
+    }
+}
+
+impl Showfile {
+    /// Dispatch every section onto its typed variant.
+    pub fn typed(&self) -> Vec<TypedSection> {
+        self.get_sections().iter().map(TypedSection::decode).collect()
+    }
+
+    /// Collect every section of a given typed form, skipping any that fail to
+    /// decode. Backs the per-identifier helpers like [`cue_stacks`](Self::cue_stacks).
+    fn collect<T: SectionData>(&self) -> Vec<T> {
+        self.decode_all::<T>().into_iter().filter_map(Result::ok).collect()
+    }
+
+    /// Every `T` — global settings — section.
+    pub fn settings(&self) -> Vec<Settings> {
+        self.collect()
+    }
+
+    /// Every `P` — fixture head — section.
+    pub fn heads(&self) -> Vec<Head> {
+        self.collect()
+    }
+
+    /// Every `L` — patched fixture — section.
+    pub fn fixtures(&self) -> Vec<Fixture> {
+        self.collect()
+    }
+
+    /// Every `F` — palette — section.
+    pub fn palettes(&self) -> Vec<Palette> {
+        self.collect()
+    }
+
+    /// Every `G` — group — section.
+    pub fn groups(&self) -> Vec<Group> {
+        self.collect()
+    }
+
+    /// Every `W` — FX — section.
+    pub fn fx(&self) -> Vec<Fx> {
+        self.collect()
+    }
+
+    /// Every `S` — playback — section.
+    pub fn playbacks(&self) -> Vec<Playback> {
+        self.collect()
+    }
+
+    /// Every `C` — cue stack — section.
+    pub fn cue_stacks(&self) -> Vec<CueStack> {
+        self.collect()
+    }
+
+    /// Every `M` — execute page — section.
+    pub fn execute_pages(&self) -> Vec<ExecutePage> {
+        self.collect()
+    }
+
+    /// Every `N` — execute item — section.
+    pub fn execute_items(&self) -> Vec<ExecuteItem> {
+        self.collect()
+    }
+
+    /// Decode the first section matching `T`'s identifier into that typed form.
+    ///
+    /// Returns `None` when the show carries no such section, or the decode
+    /// error when one is present but malformed. Pairs with the
+    /// [`SectionData`] wrappers so callers can write `show.decode::<CueStack>()`
+    /// instead of hunting for the right `Section` by hand.
+    pub fn decode<T: SectionData>(&self) -> Option<Result<T, Error>> {
+        self.get_sections()
+            .iter()
+            .find(|s| s.get_identifier().to_code() == T::IDENTIFIER)
+            .map(T::from_section)
+    }
+
+    /// Decode every section matching `T`'s identifier, in document order.
+    pub fn decode_all<T: SectionData>(&self) -> Vec<Result<T, Error>> {
+        self.get_sections()
+            .iter()
+            .filter(|s| s.get_identifier().to_code() == T::IDENTIFIER)
+            .map(T::from_section)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::showfile::{Header, LineEnding, Row, Section, Showfile, Value};
+
+    fn version_section() -> Section {
+        // V,007d,"MagicQ 1",01090307,0000,0002,
+        Section::new(
+            SectionIdentifier::Version,
+            vec![Row::from_values(vec![
+                Value::Hex(0x7d, 4),
+                Value::String("MagicQ 1".to_string()),
+                Value::Hex(0x01090307, 8),
+                Value::Hex(0, 4),
+                Value::Hex(2, 4),
+            ])],
+            0,
+        )
+    }
+
+    #[test]
+    fn version_decodes_named_fields() {
+        let section = version_section();
+        let version = Version::from_section(&section).expect("identifier matches");
+        let record = version.decode().expect("decode");
+        assert_eq!(record.name, "MagicQ 1");
+        assert_eq!(record.value0, Hex(0x7d));
+        assert_eq!(record.value1, Hex(0x01090307));
+    }
+
+    #[test]
+    fn wrapper_exposes_name_and_round_trips() {
+        let section = Section::new(
+            SectionIdentifier::CueStack,
+            vec![Row::from_values(vec![Value::Hex(1, 4), Value::String("Default".to_string())])],
+            0,
+        );
+        let show = Showfile::new(vec![Header::new("x", LineEnding::default())], vec![section.clone()]);
+
+        let stacks = show.cue_stacks();
+        assert_eq!(stacks.len(), 1);
+        assert_eq!(stacks[0].name(), Some("Default"));
+        // The raw section is preserved, so re-encoding is byte-identical.
+        assert_eq!(stacks[0].to_section().to_string(), section.to_string());
+    }
+}
@@ -0,0 +1,417 @@
+use serde::ser::{
+    self, Serialize, SerializeSeq, SerializeStruct, SerializeTuple, SerializeTupleStruct,
+    Serializer,
+};
+
+use crate::showfile::{Row, Section, SectionIdentifier, Value};
+
+use super::error::Error;
+use super::hex;
+
+/// Serialize a typed record into a single-row [`Section`] with `identifier`.
+pub fn to_section<T>(identifier: SectionIdentifier, value: &T) -> Result<Section, Error>
+where
+    T: Serialize,
+{
+    Ok(Section::new(identifier, vec![to_row(value)?], 1))
+}
+
+/// Serialize a typed record into a [`Row`].
+pub fn to_row<T>(value: &T) -> Result<Row, Error>
+where
+    T: Serialize,
+{
+    value.serialize(RowSerializer::default())
+}
+
+/// Serializer that collects a struct's fields into a [`Row`].
+#[derive(Default)]
+pub struct RowSerializer {
+    values: Vec<Value>,
+}
+
+impl RowSerializer {
+    fn field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.values.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Row {
+        Row::from_values(self.values)
+    }
+}
+
+impl Serializer for RowSerializer {
+    type Ok = Row;
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeStruct = Self;
+    type SerializeTupleVariant = ser::Impossible<Row, Error>;
+    type SerializeMap = ser::Impossible<Row, Error>;
+    type SerializeStructVariant = ser::Impossible<Row, Error>;
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Ok(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Row, Error> {
+        value.serialize(self)
+    }
+
+    // A row is always a struct/sequence of fields; a bare scalar has no columns.
+    fn serialize_i128(self, _v: i128) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_u128(self, _v: u128) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_unit(self) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+    ) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_i8(self, _v: i8) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_i16(self, _v: i16) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_i32(self, _v: i32) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_i64(self, _v: i64) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_u8(self, _v: u8) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_u16(self, _v: u16) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_u32(self, _v: u32) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_u64(self, _v: u64) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_f32(self, _v: f32) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_f64(self, _v: f64) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_char(self, _v: char) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_str(self, _v: &str) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_none(self) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<Row, Error> {
+        Err(not_a_row())
+    }
+}
+
+fn not_a_row() -> Error {
+    Error::Message("a section row must be a struct or sequence of fields".to_string())
+}
+
+impl SerializeStruct for RowSerializer {
+    type Ok = Row;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(value)
+    }
+
+    fn end(self) -> Result<Row, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeSeq for RowSerializer {
+    type Ok = Row;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.field(value)
+    }
+
+    fn end(self) -> Result<Row, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTuple for RowSerializer {
+    type Ok = Row;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.field(value)
+    }
+
+    fn end(self) -> Result<Row, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTupleStruct for RowSerializer {
+    type Ok = Row;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.field(value)
+    }
+
+    fn end(self) -> Result<Row, Error> {
+        Ok(self.finish())
+    }
+}
+
+/// Serializer mapping a single field onto a [`Value`].
+struct ValueSerializer;
+
+impl Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<Value, Error>;
+    type SerializeTuple = ser::Impossible<Value, Error>;
+    type SerializeTupleStruct = ser::Impossible<Value, Error>;
+    type SerializeTupleVariant = ser::Impossible<Value, Error>;
+    type SerializeMap = ser::Impossible<Value, Error>;
+    type SerializeStruct = ser::Impossible<Value, Error>;
+    type SerializeStructVariant = ser::Impossible<Value, Error>;
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(Value::Float(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        Ok(Value::Float(v as f64))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    // A bare integer has no fixed width; only a `Hex<W>` carries one, via the
+    // newtype-struct name below.
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(Value::Hex(v, 0))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        let ok = value.serialize(self)?;
+        match (hex::width_of(name), ok) {
+            (Some(width), Value::Hex(v, _)) => Ok(Value::Hex(v, width)),
+            (_, other) => Ok(other),
+        }
+    }
+
+    // An absent optional field is MagicQ's empty string.
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::String(String::new()))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Hex(v as u64, 0))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Value, Error> {
+        Err(unsupported("bytes"))
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::String(String::new()))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(Value::String(String::new()))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(unsupported("sequence"))
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(unsupported("tuple"))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(unsupported("tuple struct"))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(unsupported("tuple variant"))
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(unsupported("map"))
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(unsupported("nested struct"))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(unsupported("struct variant"))
+    }
+}
+
+fn unsupported(kind: &str) -> Error {
+    Error::Message(format!("a section field cannot be a {}", kind))
+}
@@ -0,0 +1,136 @@
+use std::fmt::{self, Display, Formatter};
+
+use nom::error::{VerboseError, VerboseErrorKind};
+
+use crate::span::SourceMap;
+
+/// A parse failure located in section/row/field terms.
+///
+/// `nom`'s [`convert_error`](nom::error::convert_error) dumps the whole context
+/// stack, which is unreadable on a multi-kilobyte showfile. `ParseError`
+/// instead resolves the residual input to a 1-based line and column and renders
+/// a single caret-underlined source line annotated with the innermost
+/// `context(...)` label. The full context chain stays available behind
+/// [`ParseError::verbose`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset of the failure into the original input.
+    pub offset: usize,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number.
+    pub column: usize,
+    /// The offending source line, verbatim and without its line ending.
+    pub snippet: String,
+    /// The innermost `context(...)` label, e.g. `"Hex"`.
+    pub context: String,
+    /// The full context chain, outermost first, for [`verbose`](Self::verbose).
+    contexts: Vec<String>,
+    verbose: bool,
+}
+
+impl ParseError {
+    /// Turn a nom [`VerboseError`] into a located diagnostic against `input`.
+    pub fn from_verbose(input: &str, error: VerboseError<&str>) -> ParseError {
+        // The first entry's residual slice is where parsing stalled; its start
+        // is an offset into `input` because both point into the same buffer.
+        let residual = error.errors.first().map(|(i, _)| *i).unwrap_or("");
+        let offset = input.len() - residual.len();
+
+        // One source map per failure resolves the offset and the offending line
+        // without rescanning the whole input per field.
+        let source = SourceMap::new(input);
+        let location = source.locate(offset);
+        let (line, column) = (location.line, location.column);
+        let snippet = source.line_text(offset).to_string();
+
+        let contexts: Vec<String> = error
+            .errors
+            .iter()
+            .filter_map(|(_, kind)| match kind {
+                VerboseErrorKind::Context(c) => Some(c.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        let context = contexts.first().cloned().unwrap_or_else(|| "input".to_string());
+
+        ParseError {
+            offset,
+            line,
+            column,
+            snippet,
+            context,
+            contexts,
+            verbose: false,
+        }
+    }
+
+    /// Show the full `context(...)` chain instead of just the innermost label.
+    pub fn verbose(mut self) -> Self {
+        self.verbose = true;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "parse error at line {}, column {}: expected {}",
+            self.line, self.column, self.context
+        )?;
+
+        let gutter = self.line.to_string();
+        let pad = " ".repeat(gutter.len());
+        writeln!(f, "{} |", pad)?;
+        writeln!(f, "{} | {}", gutter, self.snippet)?;
+        // The caret sits under the failing column; tabs in the snippet are kept
+        // as tabs in the underline so columns stay aligned in the terminal.
+        // Columns are byte offsets (see `SourceMap`), so walk char boundaries
+        // rather than slice — a multibyte char earlier on the line would make a
+        // byte slice panic while merely rendering the error.
+        let caret = self.column.saturating_sub(1).min(self.snippet.len());
+        let underline: String = self
+            .snippet
+            .char_indices()
+            .take_while(|(i, _)| *i < caret)
+            .map(|(_, c)| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        writeln!(f, "{} | {}^", pad, underline)?;
+
+        if self.verbose && self.contexts.len() > 1 {
+            writeln!(f, "{} | context:", pad)?;
+            for ctx in &self.contexts {
+                writeln!(f, "{} |   in {}", pad, ctx)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_a_caret_past_a_multibyte_char_without_panicking() {
+        // `column` is a byte offset; `é` is two bytes, so a caret after it lands
+        // on byte 3 — a byte slice of the snippet there would split the char and
+        // panic. Rendering must succeed regardless.
+        let error = ParseError {
+            offset: 3,
+            line: 1,
+            column: 4,
+            snippet: "aéb".to_string(),
+            context: "Field".to_string(),
+            contexts: vec!["Field".to_string()],
+            verbose: false,
+        };
+        let rendered = error.to_string();
+        assert!(rendered.contains("^"));
+    }
+}
@@ -0,0 +1,326 @@
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, BufReader, Read};
+
+use flate2::read::MultiGzDecoder;
+use nom::{
+    character::complete::line_ending,
+    combinator::map,
+    multi::many1,
+    sequence::tuple,
+    Finish,
+};
+
+use crate::error::ParseError;
+use crate::showfile::{Header, Section, Showfile};
+
+/// First two bytes of a gzip member.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Default read granularity for the streaming section reader.
+const CHUNK: usize = 64 * 1024;
+
+/// Failure loading a showfile through the IO layer.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "{}", e),
+            ReadError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(e: ParseError) -> Self {
+        ReadError::Parse(e)
+    }
+}
+
+/// Wrap `reader` in a gzip decoder when its leading bytes are the gzip magic,
+/// otherwise hand back the plain reader. Multi-member archives are supported so
+/// concatenated shows decompress as one stream.
+pub fn decode<'a, R: Read + 'a>(reader: R) -> io::Result<Box<dyn Read + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let is_gzip = {
+        let head = reader.fill_buf()?;
+        head.len() >= 2 && head[..2] == GZIP_MAGIC
+    };
+
+    if is_gzip {
+        Ok(Box::new(MultiGzDecoder::new(reader)))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+impl Showfile {
+    /// Load a showfile from any reader, transparently inflating gzip input.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Showfile, ReadError> {
+        let mut decoded = String::new();
+        decode(reader)?.read_to_string(&mut decoded)?;
+        Ok(Showfile::parse_verbose(&decoded)?)
+    }
+
+    /// Load a showfile from an in-memory byte slice, transparently inflating
+    /// gzip input. A convenience over [`from_reader`](Self::from_reader) for
+    /// callers that already hold the bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Showfile, ReadError> {
+        Showfile::from_reader(bytes)
+    }
+
+    /// Stream a (possibly gzip-compressed) showfile section by section.
+    ///
+    /// The decoded header block is consumed up front; after that each call to
+    /// [`SectionStream::next`] parses one more [`Section`] out of a growing
+    /// buffer, retaining only the unparsed tail. This lets a multi-hundred-
+    /// megabyte concatenated show be processed without holding the whole
+    /// decompressed text in memory.
+    pub fn stream_sections<R: Read + 'static>(reader: R) -> io::Result<SectionStream> {
+        Ok(SectionStream {
+            reader: decode(reader)?,
+            buf: Vec::new(),
+            done: false,
+            preamble: true,
+        })
+    }
+}
+
+fn preamble(input: &str) -> nom::IResult<&str, (), nom::error::VerboseError<&str>> {
+    map(
+        tuple((many1(Header::parse), many1(line_ending))),
+        |_| (),
+    )(input)
+}
+
+/// Iterator over the [`Section`]s of a streamed showfile.
+pub struct SectionStream {
+    reader: Box<dyn Read>,
+    buf: Vec<u8>,
+    done: bool,
+    preamble: bool,
+}
+
+impl SectionStream {
+    /// Pull another chunk off the underlying reader, returning `false` at EOF.
+    fn fill(&mut self) -> io::Result<bool> {
+        let mut chunk = [0u8; CHUNK];
+        let n = self.reader.read(&mut chunk)?;
+        if n == 0 {
+            self.done = true;
+            Ok(false)
+        } else {
+            self.buf.extend_from_slice(&chunk[..n]);
+            Ok(true)
+        }
+    }
+}
+
+impl Iterator for SectionStream {
+    type Item = Result<Section, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Only attempt a parse when the buffer is valid UTF-8; a partial
+            // trailing multibyte sequence just means we need another chunk.
+            if let Ok(text) = std::str::from_utf8(&self.buf) {
+                if self.preamble {
+                    if let Ok((rest, ())) = preamble(text) {
+                        let consumed = self.buf.len() - rest.len();
+                        self.buf.drain(..consumed);
+                        self.preamble = false;
+                        continue;
+                    }
+                } else if text.trim().is_empty() {
+                    // An empty buffer is only the end of the stream once the
+                    // reader is drained; before that it just means a section
+                    // boundary fell exactly on a read boundary, so fall through
+                    // and pull the next chunk rather than ending early.
+                    if self.done {
+                        return None;
+                    }
+                } else {
+                    // Match the raw `IResult`: `number::streaming::double` (and
+                    // a hex field with no delimiter yet) returns `Err::Incomplete`
+                    // when a read boundary lands mid-field. `.finish()` would
+                    // panic on that, so instead treat any error as "need more
+                    // bytes" until `done`, and only report a failure once the
+                    // final tail still won't parse.
+                    match Section::parse(text) {
+                        // A parse that consumed the whole buffer — or left only
+                        // a dangling `\r` from a split `\r\n` — is not yet safe
+                        // to commit while more bytes may arrive: the section's
+                        // trailing `line_ending`s use `complete` combinators, so
+                        // `many0` stops at the buffer end and would drop newlines
+                        // that belong to this section (and orphan them onto the
+                        // next). Refill until the parse leaves a real tail
+                        // behind, or until the stream is done.
+                        Ok((rest, _)) if !self.done && (rest.is_empty() || rest == "\r") => {}
+                        Ok((rest, section)) => {
+                            let consumed = self.buf.len() - rest.len();
+                            self.buf.drain(..consumed);
+                            return Some(Ok(section));
+                        }
+                        Err(_) if !self.done => {}
+                        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+                            return Some(Err(ParseError::from_verbose(text, e).into()));
+                        }
+                        Err(nom::Err::Incomplete(_)) => {
+                            return Some(Err(io::Error::new(
+                                io::ErrorKind::UnexpectedEof,
+                                "incomplete section at end of stream",
+                            )
+                            .into()));
+                        }
+                    }
+                }
+            }
+
+            match self.fill() {
+                Ok(true) => continue,
+                Ok(false) => {
+                    // No more bytes will arrive. An empty/whitespace tail is a
+                    // clean end of stream; anything else is a parse failure we
+                    // report once (looping back would spin forever since the
+                    // buffer can no longer grow).
+                    match std::str::from_utf8(&self.buf) {
+                        Ok(text) if text.trim().is_empty() => return None,
+                        Ok(text) if self.preamble => {
+                            return match preamble(text).finish() {
+                                Ok(_) => None,
+                                Err(e) => Some(Err(ParseError::from_verbose(text, e).into())),
+                            };
+                        }
+                        Ok(_) => continue,
+                        Err(_) => {
+                            return Some(Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                "stream did not end on a UTF-8 boundary",
+                            )
+                            .into()));
+                        }
+                    }
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    const SHOW: &str = "\\ MagicQ\r\n\r\nV,007d,\"x\",01090307,0000,0002,;\r\nC,0001,\"Default\";\r\n";
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn plain_text_passes_through() {
+        let show = Showfile::from_bytes(SHOW.as_bytes()).expect("parse");
+        assert_eq!(show.get_sections().len(), 2);
+    }
+
+    #[test]
+    fn gzip_is_transparently_inflated() {
+        let show = Showfile::from_bytes(&gzip(SHOW.as_bytes())).expect("parse");
+        assert_eq!(show.to_string(), SHOW);
+    }
+
+    #[test]
+    fn streams_sections_one_at_a_time() {
+        let sections: Vec<_> = Showfile::stream_sections(SHOW.as_bytes())
+            .expect("decode")
+            .collect::<Result<_, _>>()
+            .expect("parse");
+        let codes: Vec<&str> = sections.iter().map(|s| s.get_identifier().to_code()).collect();
+        assert_eq!(codes, ["V", "C"]);
+    }
+
+    /// A reader that hands out at most `step` bytes per `read`, so parsing a
+    /// section spans several refills.
+    struct Dripfeed<'a> {
+        bytes: &'a [u8],
+        pos: usize,
+        step: usize,
+    }
+
+    impl Read for Dripfeed<'_> {
+        fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+            let remaining = &self.bytes[self.pos..];
+            let n = remaining.len().min(self.step).min(out.len());
+            out[..n].copy_from_slice(&remaining[..n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn a_read_boundary_mid_field_does_not_panic() {
+        // A boundary landing inside a numeric field makes the streaming parser
+        // return `Err::Incomplete`; the reader must keep filling rather than
+        // panic on `.finish()`.
+        let drip = Dripfeed { bytes: SHOW.as_bytes(), pos: 0, step: 7 };
+        let sections: Vec<_> = Showfile::stream_sections(drip)
+            .expect("decode")
+            .collect::<Result<_, _>>()
+            .expect("parse");
+        let codes: Vec<&str> = sections.iter().map(|s| s.get_identifier().to_code()).collect();
+        assert_eq!(codes, ["V", "C"]);
+    }
+
+    /// A reader that returns the input in two reads, splitting at a fixed byte.
+    struct SplitAt<'a> {
+        bytes: &'a [u8],
+        pos: usize,
+        split: usize,
+    }
+
+    impl Read for SplitAt<'_> {
+        fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+            let end = if self.pos < self.split { self.split } else { self.bytes.len() };
+            let remaining = &self.bytes[self.pos..end];
+            let n = remaining.len().min(out.len());
+            out[..n].copy_from_slice(&remaining[..n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn a_boundary_on_a_section_end_drops_nothing() {
+        // Whatever byte a read happens to end on, every section must still come
+        // out: a boundary that lands exactly where one section ends drains the
+        // buffer to empty, which must not be mistaken for end of stream while
+        // the reader still has bytes to give.
+        for split in 1..SHOW.len() {
+            let reader = SplitAt { bytes: SHOW.as_bytes(), pos: 0, split };
+            let sections: Vec<_> = Showfile::stream_sections(reader)
+                .expect("decode")
+                .collect::<Result<_, _>>()
+                .unwrap_or_else(|e| panic!("split at {split}: {e}"));
+            let codes: Vec<&str> =
+                sections.iter().map(|s| s.get_identifier().to_code()).collect();
+            assert_eq!(codes, ["V", "C"], "split at {split}");
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Language-server subsystem for editing `.shw` files.
+//!
+//! Feature-gated behind `lsp` so the core library and CLI don't pull in the
+//! async runtime. The server reuses the parser and the [`span`](crate::span)
+//! tracking: parse failures become [`Diagnostic`]s positioned by the located
+//! [`ParseError`](crate::error::ParseError), each [`Section`] becomes a
+//! navigable [`DocumentSymbol`], and hover over a row names its owning section.
+//!
+//! Run it with the `av-lsp` binary (also behind the `lsp` feature) over stdio.
+
+use std::collections::HashMap;
+
+use tower_lsp::jsonrpc::Result;
+use tower_lsp::lsp_types::*;
+use tower_lsp::{Client, LanguageServer};
+
+use crate::showfile::{SectionIdentifier, Showfile};
+use crate::span::{Location, SourceMap, Span};
+
+/// A human-readable label for a section identifier, used in the outline and
+/// hover text.
+fn label(identifier: &SectionIdentifier) -> String {
+    match identifier {
+        SectionIdentifier::Version => "Version".to_string(),
+        SectionIdentifier::Settings => "Settings".to_string(),
+        SectionIdentifier::Head => "Head".to_string(),
+        SectionIdentifier::Fixture => "Fixture".to_string(),
+        SectionIdentifier::Palette => "Palette".to_string(),
+        SectionIdentifier::Group => "Group".to_string(),
+        SectionIdentifier::FX => "FX".to_string(),
+        SectionIdentifier::Playback => "Playback".to_string(),
+        SectionIdentifier::CueStack => "Cue Stack".to_string(),
+        SectionIdentifier::ExecutePage => "Execute Page".to_string(),
+        SectionIdentifier::ExecuteItem => "Execute Item".to_string(),
+        SectionIdentifier::Unknown(code) => format!("Unknown `{}`", code),
+    }
+}
+
+fn position(location: Location) -> Position {
+    // `SourceMap` is 1-based; LSP positions are 0-based. Columns are counted in
+    // bytes, which matches the editor for the ASCII text MagicQ emits.
+    Position {
+        line: (location.line - 1) as u32,
+        character: (location.column - 1) as u32,
+    }
+}
+
+fn range(map: &SourceMap, span: Span) -> Range {
+    let (start, end) = map.span(span);
+    Range { start: position(start), end: position(end) }
+}
+
+/// Parse `text` and report any failure as a single diagnostic.
+pub fn diagnostics(text: &str) -> Vec<Diagnostic> {
+    match Showfile::parse_verbose(text) {
+        Ok(_) => Vec::new(),
+        Err(error) => {
+            let at = position(Location { line: error.line, column: error.column });
+            vec![Diagnostic {
+                range: Range { start: at, end: at },
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("magicq".to_string()),
+                message: error.to_string(),
+                ..Default::default()
+            }]
+        }
+    }
+}
+
+/// A flat outline of the show's sections, for `textDocument/documentSymbol`.
+pub fn document_symbols(text: &str) -> Vec<DocumentSymbol> {
+    let Ok(show) = Showfile::parse_verbose(text) else {
+        return Vec::new();
+    };
+    let map = SourceMap::new(text);
+
+    show.get_sections()
+        .iter()
+        .map(|section| {
+            let range = range(&map, section.span());
+            #[allow(deprecated)]
+            DocumentSymbol {
+                name: label(section.get_identifier()),
+                detail: Some(format!("{} row(s)", section.get_rows().len())),
+                kind: SymbolKind::STRUCT,
+                tags: None,
+                deprecated: None,
+                range,
+                selection_range: range,
+                children: None,
+            }
+        })
+        .collect()
+}
+
+/// Hover text for the row under `at`, naming its section and column.
+pub fn hover(text: &str, at: Position) -> Option<Hover> {
+    let map = SourceMap::new(text);
+    let offset = map.offset(Location {
+        line: at.line as usize + 1,
+        column: at.character as usize + 1,
+    });
+    let show = Showfile::parse_verbose(text).ok()?;
+
+    let section = show
+        .get_sections()
+        .iter()
+        .find(|s| s.span().start <= offset && offset < s.span().end)?;
+    let (row_index, row) = section
+        .get_rows()
+        .iter()
+        .enumerate()
+        .find(|(_, r)| r.span().start <= offset && offset < r.span().end)?;
+
+    let contents = format!(
+        "**{}** (`{}`) — row {} of {}, {} field(s)",
+        label(section.get_identifier()),
+        section.get_identifier().to_code(),
+        row_index + 1,
+        section.get_rows().len(),
+        row.get_values().len(),
+    );
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: contents,
+        }),
+        range: Some(range(&map, row.span())),
+    })
+}
+
+/// The LSP backend. Keeps the latest text of each open document so edits can be
+/// re-analysed without touching disk.
+pub struct Backend {
+    client: Client,
+    documents: tokio::sync::Mutex<HashMap<Url, String>>,
+}
+
+impl Backend {
+    pub fn new(client: Client) -> Backend {
+        Backend {
+            client,
+            documents: tokio::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Re-parse a document and push its diagnostics to the client.
+    async fn publish(&self, uri: Url, text: String) {
+        let diagnostics = diagnostics(&text);
+        self.documents.lock().await.insert(uri.clone(), text);
+        self.client.publish_diagnostics(uri, diagnostics, None).await;
+    }
+}
+
+#[tower_lsp::async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            server_info: Some(ServerInfo {
+                name: "av-lsp".to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+        })
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        let doc = params.text_document;
+        self.publish(doc.uri, doc.text).await;
+    }
+
+    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
+        // Full-sync, so the last content change holds the whole document.
+        if let Some(change) = params.content_changes.pop() {
+            self.publish(params.text_document.uri, change.text).await;
+        }
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let documents = self.documents.lock().await;
+        let Some(text) = documents.get(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        Ok(Some(DocumentSymbolResponse::Nested(document_symbols(text))))
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let position = params.text_document_position_params;
+        let documents = self.documents.lock().await;
+        let Some(text) = documents.get(&position.text_document.uri) else {
+            return Ok(None);
+        };
+        Ok(hover(text, position.position))
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -1,49 +1,274 @@
-use std::str::FromStr;
-use std::{
-    env,
-    fs,
-    process,
-};
-use std::collections::HashMap;
-use magicq::{Showfile, SectionIdentifier};
-
-fn main() {
-     let args: Vec<String> = env::args().collect();
-
-    if args.len() < 2 {
-        eprintln!("Usage: {} <filename>", args[0]);
-        process::exit(1);
-    }
-
-    let filename = &args[1];
-    let input = match fs::read_to_string(filename) {
-        Ok(contents) => contents,
+use std::fs::File;
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use magicq::io::{decode, ReadError};
+use magicq::showfile::{SectionIdentifier, Showfile};
+
+/// Tools for inspecting and reformatting MagicQ showfiles.
+#[derive(Debug, Parser)]
+#[command(name = "av", version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Validate a showfile and report the first diagnostic.
+    Parse {
+        /// Showfile to read, or `-` for stdin.
+        file: String,
+    },
+    /// Parse a showfile and report any round-trip mismatch.
+    Validate {
+        /// Showfile to read, or `-` for stdin.
+        file: String,
+    },
+    /// Summarise a showfile by section count.
+    Stats {
+        /// Showfile to read, or `-` for stdin.
+        file: String,
+    },
+    /// List or dump the sections of a showfile.
+    Sections {
+        /// Showfile to read, or `-` for stdin.
+        file: String,
+        /// Restrict output to sections with this identifier code, e.g. `C`.
+        #[arg(long)]
+        id: Option<String>,
+    },
+    /// Mine rows out of a showfile by section, filter and column projection.
+    Query {
+        /// Showfile to read, or `-` for stdin.
+        file: String,
+        /// Section identifier code to select, e.g. `C`.
+        id: String,
+        /// Row filter `col<op>value`, e.g. `1=Default`; repeatable (AND).
+        #[arg(long = "where")]
+        predicate: Vec<String>,
+        /// Comma-separated column indices to project, e.g. `0,1`.
+        #[arg(long)]
+        cols: Option<String>,
+    },
+    /// Round-trip a showfile through the parser and print it back out.
+    Fmt {
+        /// Showfile to read, or `-` for stdin.
+        file: String,
+    },
+    /// Convert a showfile to or from another representation.
+    Convert {
+        /// Showfile to read, or `-` for stdin.
+        file: String,
+        /// Input format.
+        #[arg(long, value_enum, default_value_t = Format::Shw)]
+        from: Format,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        to: Format,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Native MagicQ show text.
+    Shw,
+    /// JSON tree of headers, sections, rows and values.
+    Json,
+    /// Flat CSV, one line per row prefixed with its section identifier.
+    Csv,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
-            eprintln!("Error reading file {}: {}", filename, e);
-            process::exit(1);
+            eprintln!("{}", e);
+            ExitCode::FAILURE
         }
-    };
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    match cli.command {
+        Command::Parse { file } => {
+            load(&file)?;
+            eprintln!("{}: ok", file);
+            Ok(())
+        }
+        Command::Validate { file } => validate(&file),
+        Command::Stats { file } => {
+            let show = load(&file)?;
+            stats(&show);
+            Ok(())
+        }
+        Command::Sections { file, id } => {
+            let show = load(&file)?;
+            sections(&show, id.as_deref());
+            Ok(())
+        }
+        Command::Query { file, id, predicate, cols } => {
+            let show = load(&file)?;
+            query(&show, &id, &predicate, cols.as_deref())
+        }
+        Command::Fmt { file } => {
+            let show = load(&file)?;
+            print!("{}", show);
+            Ok(())
+        }
+        Command::Convert { file, from, to } => convert(&file, from, to),
+    }
+}
+
+/// Parse a showfile and confirm that re-emitting it reproduces the input
+/// byte-for-byte, reporting the first divergence otherwise.
+fn validate(file: &str) -> Result<(), String> {
+    let text = read_decoded(file)?;
+    let show = Showfile::parse_verbose(&text).map_err(|e| format!("{}:\n{}", file, e))?;
+    let written = show.to_string();
+
+    if written == text {
+        eprintln!("{}: ok", file);
+        Ok(())
+    } else {
+        let at = text
+            .char_indices()
+            .zip(written.char_indices())
+            .find(|((_, a), (_, b))| a != b)
+            .map(|((i, _), _)| i)
+            .unwrap_or_else(|| text.len().min(written.len()));
+        Err(format!("{}: round-trip mismatch near byte {}", file, at))
+    }
+}
+
+fn stats(show: &Showfile) {
+    use std::collections::BTreeMap;
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for section in show.get_sections() {
+        *counts.entry(section.get_identifier().to_code()).or_default() += 1;
+    }
 
-    let showfile = Showfile::from_str(&input).unwrap_or_else(|e| {
-        eprintln!("Error: {}", e);
-        process::exit(1);
-    });
+    println!("{} headers", show.get_headers().len());
+    println!("{} sections", show.get_sections().len());
+    for (code, count) in counts {
+        println!("  {}: {}", code, count);
+    }
+}
+
+fn sections(show: &Showfile, id: Option<&str>) {
+    let wanted = id.map(SectionIdentifier::from_code);
+    for section in show.get_sections() {
+        if wanted.as_ref().is_none_or(|id| id == section.get_identifier()) {
+            print!("{}", section);
+        }
+    }
+}
+
+fn query(show: &Showfile, id: &str, predicates: &[String], cols: Option<&str>) -> Result<(), String> {
+    let mut q = show.query().section(SectionIdentifier::from_code(id));
+    for expr in predicates {
+        q = q.filter(magicq::query::Predicate::parse(expr)?);
+    }
+
+    match cols {
+        Some(spec) => {
+            let cols = spec
+                .split(',')
+                .map(|c| c.trim().parse::<usize>().map_err(|_| format!("invalid column `{}`", c.trim())))
+                .collect::<Result<Vec<_>, _>>()?;
+            for row in q.select(cols) {
+                let line = row
+                    .into_iter()
+                    .map(|v| v.map(|v| v.to_string()).unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                println!("{}", line);
+            }
+        }
+        None => {
+            for row in q.rows() {
+                print!("{}", row);
+            }
+        }
+    }
 
-    let res = showfile.get_sections().into_iter().fold(HashMap::new(), |mut acc, item| {
-        *acc.entry(item.get_identifier()).or_insert(0) += 1;
-        acc
-    });
+    Ok(())
+}
 
-    // Print a list of CueStacks
-    let cuestacks = showfile.get_sections().iter()
-        .filter(|section| section.get_identifier() == &SectionIdentifier::CueStack)
-        .map(|section| section[0][1].to_string());
-    for cuestack in cuestacks {
-        println!("{}", cuestack);
+fn convert(file: &str, from: Format, to: Format) -> Result<(), String> {
+    let show = match from {
+        Format::Shw => load(file)?,
+        Format::Json => {
+            let text = read_text(file)?;
+            serde_json::from_str(&text).map_err(|e| format!("invalid JSON: {}", e))?
+        }
+        Format::Csv => return Err("CSV is an export-only format".to_string()),
+    };
+
+    match to {
+        Format::Shw => print!("{}", show),
+        Format::Json => {
+            let json = serde_json::to_string_pretty(&show)
+                .map_err(|e| format!("could not encode JSON: {}", e))?;
+            println!("{}", json);
+        }
+        Format::Csv => print!("{}", to_csv(&show)),
     }
 
-    for counts in res {
-        println!("{:?}", counts);
+    Ok(())
+}
+
+/// Flatten a showfile to CSV: each line is a section identifier followed by the
+/// `Display` form of every field in a row.
+fn to_csv(show: &Showfile) -> String {
+    let mut out = String::new();
+    for section in show.get_sections() {
+        let code = section.get_identifier().to_code();
+        for row in section.get_rows() {
+            out.push_str(code);
+            for value in row.get_values() {
+                out.push(',');
+                out.push_str(&value.to_string());
+            }
+            out.push('\n');
+        }
     }
+    out
+}
+
+/// Parse a showfile through the gzip-aware reader, rendering a located
+/// diagnostic on failure.
+fn load(file: &str) -> Result<Showfile, String> {
+    Showfile::from_reader(open(file)?).map_err(|e| match e {
+        ReadError::Io(e) => format!("{}: {}", file, e),
+        ReadError::Parse(e) => format!("{}:\n{}", file, e),
+    })
+}
+
+fn read_decoded(file: &str) -> Result<String, String> {
+    let mut text = String::new();
+    decode(open(file)?)
+        .and_then(|mut r| r.read_to_string(&mut text))
+        .map_err(|e| format!("{}: {}", file, e))?;
+    Ok(text)
+}
 
-}
\ No newline at end of file
+fn read_text(file: &str) -> Result<String, String> {
+    let mut text = String::new();
+    open(file)?
+        .read_to_string(&mut text)
+        .map_err(|e| format!("{}: {}", file, e))?;
+    Ok(text)
+}
+
+fn open(file: &str) -> Result<Box<dyn Read>, String> {
+    if file == "-" {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        File::open(file)
+            .map(|f| Box::new(f) as Box<dyn Read>)
+            .map_err(|e| format!("{}: {}", file, e))
+    }
+}
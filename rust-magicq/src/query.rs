@@ -0,0 +1,364 @@
+//! Non-destructive query/projection layer over a parsed [`Showfile`].
+//!
+//! Writing a typed [`SectionData`](crate::data::SectionData) struct is the right
+//! move when you need every field of a section; for ad-hoc mining — "the fade
+//! time of every cue stack", "the palette referenced by this cue" — it is a lot
+//! of ceremony. [`Showfile::query`] opens a fluent, column-oriented builder:
+//! restrict to a [`SectionIdentifier`], filter rows with [`Predicate`]s built
+//! from [`col`], and either collect the matching [`Row`]s or project them down
+//! to a fixed set of columns.
+
+use std::cmp::Ordering;
+
+use crate::showfile::{Row, SectionIdentifier, Showfile, Value};
+
+impl Showfile {
+    /// Start a fluent, column-oriented query over the show.
+    ///
+    /// ```no_run
+    /// # use magicq::showfile::{Showfile, SectionIdentifier};
+    /// # use magicq::query::col;
+    /// # let show: Showfile = unimplemented!();
+    /// let rows = show
+    ///     .query()
+    ///     .section(SectionIdentifier::CueStack)
+    ///     .filter(col(1).eq("Default"))
+    ///     .select([0, 1, 3]);
+    /// ```
+    pub fn query(&self) -> Query<'_> {
+        Query {
+            show: self,
+            identifier: None,
+            predicates: Vec::new(),
+        }
+    }
+}
+
+/// A builder for a read-only query over a [`Showfile`]. See [`Showfile::query`].
+pub struct Query<'a> {
+    show: &'a Showfile,
+    identifier: Option<SectionIdentifier>,
+    predicates: Vec<Predicate>,
+}
+
+impl<'a> Query<'a> {
+    /// Restrict the query to sections with this identifier.
+    pub fn section(mut self, identifier: SectionIdentifier) -> Self {
+        self.identifier = Some(identifier);
+        self
+    }
+
+    /// Keep only rows satisfying `predicate`; repeated calls AND together.
+    pub fn filter(mut self, predicate: Predicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
+    /// Collect the matching rows.
+    pub fn rows(self) -> std::vec::IntoIter<&'a Row> {
+        let Query { show, identifier, predicates } = self;
+        let rows: Vec<&Row> = show
+            .get_sections()
+            .iter()
+            .filter(|s| identifier.as_ref().is_none_or(|id| id == s.get_identifier()))
+            .flat_map(|s| s.get_rows().iter())
+            .filter(|row| predicates.iter().all(|p| p.matches(row)))
+            .collect();
+        rows.into_iter()
+    }
+
+    /// Return the first row satisfying the query, or `None`.
+    ///
+    /// The single-row counterpart to [`rows`](Self::rows), for cross-section id
+    /// resolution — e.g. locating the palette a cue stack references:
+    ///
+    /// ```no_run
+    /// # use magicq::showfile::{Showfile, SectionIdentifier};
+    /// # use magicq::query::col;
+    /// # let show: Showfile = unimplemented!();
+    /// # let palette_id = 0u64;
+    /// let palette = show
+    ///     .query()
+    ///     .section(SectionIdentifier::Palette)
+    ///     .find(col(0).eq(palette_id));
+    /// ```
+    pub fn find(self, predicate: Predicate) -> Option<&'a Row> {
+        self.filter(predicate).rows().next()
+    }
+
+    /// Collect the matching rows, projected down to `cols`.
+    ///
+    /// A column a row lacks is `None` in that slot, so every output row is
+    /// `cols.len()` wide.
+    pub fn select(self, cols: impl Into<Vec<usize>>) -> Vec<Vec<Option<&'a Value>>> {
+        let cols = cols.into();
+        self.rows()
+            .map(|row| cols.iter().map(|&c| row.get(c)).collect())
+            .collect()
+    }
+}
+
+/// Start building a predicate against column `index`. See [`Column`].
+pub fn col(index: usize) -> Column {
+    Column { index }
+}
+
+/// A column reference used to build a [`Predicate`] via a comparison method.
+pub struct Column {
+    index: usize,
+}
+
+impl Column {
+    fn op(self, op: Op, operand: impl Into<Operand>) -> Predicate {
+        Predicate { col: self.index, op, operand: operand.into() }
+    }
+
+    /// Column equals the operand.
+    pub fn eq(self, operand: impl Into<Operand>) -> Predicate {
+        self.op(Op::Eq, operand)
+    }
+
+    /// Column does not equal the operand.
+    pub fn ne(self, operand: impl Into<Operand>) -> Predicate {
+        self.op(Op::Ne, operand)
+    }
+
+    /// Column is strictly less than the operand.
+    pub fn lt(self, operand: impl Into<Operand>) -> Predicate {
+        self.op(Op::Lt, operand)
+    }
+
+    /// Column is strictly greater than the operand.
+    pub fn gt(self, operand: impl Into<Operand>) -> Predicate {
+        self.op(Op::Gt, operand)
+    }
+
+    /// Column is less than or equal to the operand.
+    pub fn le(self, operand: impl Into<Operand>) -> Predicate {
+        self.op(Op::Le, operand)
+    }
+
+    /// Column is greater than or equal to the operand.
+    pub fn ge(self, operand: impl Into<Operand>) -> Predicate {
+        self.op(Op::Ge, operand)
+    }
+}
+
+/// A comparison operator on a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+/// The right-hand side of a comparison, compared against a field per its
+/// [`Value`] variant: numerically for [`Value::Float`], as an integer for
+/// [`Value::Hex`], lexically for [`Value::String`].
+#[derive(Debug, Clone)]
+pub struct Operand(String);
+
+impl From<&str> for Operand {
+    fn from(s: &str) -> Self {
+        Operand(s.to_string())
+    }
+}
+
+impl From<String> for Operand {
+    fn from(s: String) -> Self {
+        Operand(s)
+    }
+}
+
+impl From<u64> for Operand {
+    fn from(v: u64) -> Self {
+        Operand(v.to_string())
+    }
+}
+
+impl From<f64> for Operand {
+    fn from(v: f64) -> Self {
+        Operand(v.to_string())
+    }
+}
+
+impl Operand {
+    fn as_f64(&self) -> Option<f64> {
+        self.0.parse().ok()
+    }
+
+    /// Parse the operand as an integer, accepting decimal or bare hex so a
+    /// `--where 0=1a` reads naturally against a hex column.
+    fn as_u64(&self) -> Option<u64> {
+        self.0
+            .parse()
+            .ok()
+            .or_else(|| u64::from_str_radix(&self.0, 16).ok())
+    }
+
+    fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A single column comparison. Build one with [`col`].
+#[derive(Debug, Clone)]
+pub struct Predicate {
+    col: usize,
+    op: Op,
+    operand: Operand,
+}
+
+impl Predicate {
+    /// Parse a `col<op>operand` expression, e.g. `1=Default` or `3>=5`.
+    ///
+    /// Supports `=`, `!=`, `<`, `>`, `<=`, `>=`; longer operators are tried
+    /// first so `<=` isn't mistaken for `<`.
+    pub fn parse(expr: &str) -> Result<Predicate, String> {
+        for (tag, op) in [
+            ("!=", Op::Ne),
+            ("<=", Op::Le),
+            (">=", Op::Ge),
+            ("=", Op::Eq),
+            ("<", Op::Lt),
+            (">", Op::Gt),
+        ] {
+            if let Some((lhs, rhs)) = expr.split_once(tag) {
+                let col = lhs
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid column index `{}`", lhs.trim()))?;
+                return Ok(Predicate { col, op, operand: Operand(rhs.trim().to_string()) });
+            }
+        }
+        Err(format!("`{}` is not a `col<op>value` expression", expr))
+    }
+
+    fn matches(&self, row: &Row) -> bool {
+        let Some(value) = row.get(self.col) else {
+            return false;
+        };
+        let ordering = order(value, &self.operand);
+        match self.op {
+            Op::Eq => ordering == Some(Ordering::Equal),
+            // An operand that can't be read as this column's type yields `None`;
+            // treat that as no match, consistent with every other operator,
+            // rather than letting `!=` select every such row.
+            Op::Ne => matches!(ordering, Some(Ordering::Less | Ordering::Greater)),
+            Op::Lt => ordering == Some(Ordering::Less),
+            Op::Gt => ordering == Some(Ordering::Greater),
+            Op::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            Op::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        }
+    }
+}
+
+/// Order a field against an operand using the field's variant. Returns `None`
+/// when the operand can't be read as the field's type (e.g. comparing a hex
+/// column against a non-numeric operand), which every operator treats as "no
+/// match".
+fn order(value: &Value, operand: &Operand) -> Option<Ordering> {
+    match value {
+        Value::Float(f) => operand.as_f64().and_then(|o| f.partial_cmp(&o)),
+        Value::Hex(v, _) => operand.as_u64().map(|o| v.cmp(&o)),
+        Value::String(s) => Some(s.as_str().cmp(operand.as_str())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::showfile::{Header, LineEnding, Row, Section, Showfile, Value};
+
+    fn show() -> Showfile {
+        // Two cue stacks and a palette, enough to exercise filtering,
+        // projection and a cross-section lookup.
+        let cues = Section::new(
+            SectionIdentifier::CueStack,
+            vec![
+                Row::from_values(vec![Value::Hex(1, 4), Value::String("Default".into()), Value::Float(3.0)]),
+                Row::from_values(vec![Value::Hex(2, 4), Value::String("Chase".into()), Value::Float(1.5)]),
+            ],
+            0,
+        );
+        let palettes = Section::new(
+            SectionIdentifier::Palette,
+            vec![Row::from_values(vec![Value::Hex(2, 4), Value::String("Blue".into())])],
+            0,
+        );
+        Showfile::new(vec![Header::new("x", LineEnding::default())], vec![cues, palettes])
+    }
+
+    #[test]
+    fn filters_by_string_column() {
+        let show = show();
+        let rows: Vec<_> = show
+            .query()
+            .section(SectionIdentifier::CueStack)
+            .filter(col(1).eq("Default"))
+            .rows()
+            .collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0][1].as_str(), Some("Default"));
+    }
+
+    #[test]
+    fn numeric_compare_on_float_column() {
+        let show = show();
+        let rows: Vec<_> = show
+            .query()
+            .section(SectionIdentifier::CueStack)
+            .filter(col(2).lt(2.0))
+            .rows()
+            .collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0][1].as_str(), Some("Chase"));
+    }
+
+    #[test]
+    fn projection_pads_missing_columns() {
+        let show = show();
+        let projected = show
+            .query()
+            .section(SectionIdentifier::Palette)
+            .select([0, 5]);
+        assert_eq!(projected.len(), 1);
+        assert_eq!(projected[0][0].and_then(Value::as_u64), Some(2));
+        assert!(projected[0][1].is_none());
+    }
+
+    #[test]
+    fn find_resolves_a_cross_section_id() {
+        let show = show();
+        let palette = show
+            .query()
+            .section(SectionIdentifier::Palette)
+            .find(col(0).eq(2u64))
+            .expect("palette 2 exists");
+        assert_eq!(palette[1].as_str(), Some("Blue"));
+    }
+
+    #[test]
+    fn ne_rejects_unparseable_operand() {
+        let show = show();
+        // A non-numeric operand can't be read as a hex column, so `!=` must not
+        // select every row.
+        let rows: Vec<_> = show
+            .query()
+            .section(SectionIdentifier::CueStack)
+            .filter(col(0).ne("oops"))
+            .rows()
+            .collect();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn parses_predicate_expressions() {
+        assert!(Predicate::parse("1=Default").is_ok());
+        assert!(Predicate::parse("3>=5").is_ok());
+        assert!(Predicate::parse("nonsense").is_err());
+    }
+}
@@ -1,468 +1,709 @@
-use std::{
-    fmt::{self, Display, Formatter},
-    ops::{Index, IndexMut},
-    str::FromStr,
-};
-use itertools::Itertools;
-use nom::{
-    branch::alt,
-    bytes::complete::{tag, escaped},
-    character::complete::{hex_digit1, line_ending, none_of, char, not_line_ending, alphanumeric1},
-    combinator::{peek, eof, map, map_res, rest},
-    multi::{many0, many1, many_till},
-    sequence::{terminated, delimited, tuple},
-    error::{VerboseError, context, convert_error},
-    IResult, number::streaming::double, Parser, Finish,
-};
-
-static LINE_RETURN: &str = "\n";
-
-#[derive(Debug)]
-pub struct Header(String);
-
-impl Header {
-    pub fn new(value: &str) -> Header {
-        Header(value.to_string())
-    }
-
-    pub fn parse(input: &str) -> IResult<&str, Header, VerboseError<&str>> {
-        context(
-            "Parsing Header", 
-            map(
-                delimited(
-                    tag("\\ "),
-                    not_line_ending,
-                    line_ending,
-                ),
-                Header::new,
-            ),
-        )(input)
-    }
-}
-
-impl Display for Header {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Header(value) = self;
-        write!(f, "\\ {}{}", value, LINE_RETURN)
-    }
-}
-
-#[derive(Debug)]
-pub enum Value {
-    Float(f64),
-    String(String),
-    Hex(u64, usize),
-}
-
-impl Value {
-    fn parse_string(input: &str) -> IResult<&str, (Value, bool), VerboseError<&str>> {
-        context(
-            "String",
-            map(
-                tuple((
-                    alt((
-                        delimited(
-                            char('\"'),
-                            escaped(none_of("\""), '\\', char('\"')),
-                            char('\"'),
-                        ),
-                        map(tag("\"\""), |_| ""),
-                    )),
-                    alt((
-                        map(tag(","), |_| true),
-                        map(peek(tag(";")), |_| false),
-                        map(peek(line_ending), |_| false),
-                    )),
-                )),
-                |(s, c)| (Value::String(s.to_string()), c),
-            )
-        )(input)
-    }
-
-    fn parse_float(input: &str) -> IResult<&str, (Value, bool), VerboseError<&str>> {
-        context(
-            "Float",
-            map(
-                tuple((
-                    alt((
-                        double,
-                        map(tag("nan"), |_| f64::NAN),
-                        map(tag("-nan"), |_| -f64::NAN),
-                    )),
-                    alt((
-                        map(tag(","), |_| true),
-                        map(peek(tag(";")), |_| false),
-                        map(peek(line_ending), |_| false),
-                    )),
-                )),
-                |(f, c)| (Value::Float(f), c)
-            ),
-        )(input)
-    }
-
-    fn parse_hex(input: &str) -> IResult<&str, (Value, bool), VerboseError<&str>> {
-        context(
-            "Hex",
-            map_res(
-                tuple((
-                    hex_digit1.and(peek(rest.map(|r: &str| input.len() - r.len()))),
-                    alt((
-                        map(tag(","), |_| true),
-                        map(peek(tag(";")), |_| false),
-                        map(peek(line_ending), |_| false),
-                    )),
-                )),
-                |((h, l), c)| {
-                    u64::from_str_radix(h, 16).map(|v| (Value::Hex(v, l), c))
-                },
-            ),
-        )(input)
-}
-
-    pub fn parse(input: &str) -> IResult<&str, (Value, bool), VerboseError<&str>> {
-        context(
-            "Field",
-            alt((Self::parse_string, Self::parse_hex, Self::parse_float)),
-        )(input)
-    }
-}
-
-impl Display for Value {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Value::Float(fl) => {
-                // Dirty hack because MagicQ sometimes writes out both
-                // nan and -nan. Please don't ask why it needs -nan.
-                if fl.is_nan() {
-                    write!(f, "{}", if fl.is_sign_positive() {
-                        "nan"
-                    } else {
-                        "-nan"
-                    })
-                } else {
-                    write!(f, "{:.6}", fl)
-                }
-            },
-            Value::String(s) => write!(f, "\"{}\"", s),
-            Value::Hex(h, w) => {
-                // Dirty hack because MagicQ sometimes writes out hex values
-                // in both upper case and lower case and I don't know why.
-                // If this breaks add a test case and figure out what the new
-                // terrible hack is to keep it happy.
-                if *w == 16 { 
-                    write!(f, "{:0width$X}", h, width = w)
-                } else {
-                    write!(f, "{:0width$x}", h, width = w)
-                }
-            },
-        }
-    }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub enum SectionIdentifier {
-    Version,
-    Settings,
-    Head,
-    Fixture,
-    Palette,
-    Group,
-    FX,
-    Playback,
-    CueStack,
-    ExecutePage,
-    ExecuteItem,
-    Unknown(String)
-}
-
-impl SectionIdentifier {
-    pub fn from_code(s: &str) -> SectionIdentifier {
-        match s {
-            "V" => SectionIdentifier::Version,
-            "T" => SectionIdentifier::Settings,
-            "P" => SectionIdentifier::Head,
-            "L" => SectionIdentifier::Fixture,
-            "F" => SectionIdentifier::Palette,
-            "G" => SectionIdentifier::Group,
-            "W" => SectionIdentifier::FX,
-            "S" => SectionIdentifier::Playback,
-            "C" => SectionIdentifier::CueStack,
-            "M" => SectionIdentifier::ExecutePage,
-            "N" => SectionIdentifier::ExecuteItem,
-            //"r" => SectionIdentifier::Unknown("r"),
-            //"Q" => SectionIdentifier::Unknown("Q"),
-            //"R" => SectionIdentifier::Unknown("R"),
-            //"Z" => SectionIdentifier::Unknown("Z"),
-            //"J" => SectionIdentifier::Unknown("J"),
-            //"u" => SectionIdentifier::Unknown("u"),
-            //"H" => SectionIdentifier::Unknown("H"),
-            //"E1" => SectionIdentifier::Unknown("E1"),
-            //"Y" => SectionIdentifier::Unknown("Y"),
-            _ => SectionIdentifier::Unknown(i.to_string()),
-        }
-    }
-    
-    pub fn to_code(&self) -> &str {
-        match self {
-            SectionIdentifier::Version => "V",
-            SectionIdentifier::Settings => "T",
-            SectionIdentifier::Head => "P",
-            SectionIdentifier::Fixture => "L",
-            SectionIdentifier::Palette => "F",
-            SectionIdentifier::Group => "G",
-            SectionIdentifier::FX => "W",
-            SectionIdentifier::Playback => "S",
-            SectionIdentifier::CueStack => "C",
-            SectionIdentifier::ExecutePage => "M",
-            SectionIdentifier::ExecuteItem => "N",
-            //SectionIdentifier::Unknown("r") => "r",
-            //SectionIdentifier::Unknown("Q") => "Q",
-            //SectionIdentifier::Unknown("R") => "R",
-            //SectionIdentifier::Unknown("Z") => "Z",
-            //SectionIdentifier::Unknown("J") => "J",
-            //SectionIdentifier::Unknown("u") => "u",
-            //SectionIdentifier::Unknown("H") => "H",
-            //SectionIdentifier::Unknown("E1") => "E1",
-            //SectionIdentifier::Unknown("Y") => "Y",
-            SectionIdentifier::Unknown(s) => s,
-        }
-    }
-
-    fn parse(input: &str) -> IResult<&str, SectionIdentifier, VerboseError<&str>> {
-        context(
-            "Section Identifier",
-            map(
-                alphanumeric1,
-                SectionIdentifier::from_code,
-            )
-        )(input)
-    }
-}
-
-#[derive(Debug)]
-pub struct Row {
-    values: Vec<Value>,
-    trailing_comma: bool,
-    trailing_newlines: usize,
-}
-
-impl Row {
-    fn new(values: Vec<Value>, trailing_comma: bool, trailing_newlines: usize) -> Self {
-        Self { values, trailing_comma, trailing_newlines }
-    }
-
-    fn parse(input: &str) -> IResult<&str, Row, VerboseError<&str>> {
-        context(
-            "Row",
-            map(
-                tuple((
-                    many1(Value::parse),
-                    alt((
-                        map(many1(line_ending), |l| l.len()),
-                        map(peek(tag(";")), |_| 0),
-                    )),
-                )),
-                |(r, n)| {
-                    let comma = r.last().map(|t| t.1).unwrap_or(false);
-                    let values = r.into_iter().map(|t| t.0).collect_vec();
-                    Row::new(values, comma, n)
-                },
-            ),
-        )(input)
-    }
-
-    pub fn has_trailing_comma(&self) -> bool {
-        self.trailing_comma
-    }
-
-    pub fn get_trailing_newlines(&self) -> usize {
-        self.trailing_newlines
-    }
-}
-
-impl<'a> IntoIterator for &'a Row {
-    type Item = &'a Value;
-    type IntoIter = std::slice::Iter<'a, Value>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.values.iter()
-    }
-}
-
-impl Index<usize> for Row {
-    type Output = Value;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.values[index]
-    }
-}
-
-impl IndexMut<usize> for Row {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.values[index]
-    }
-}
-
-impl Default for Row {
-    fn default() -> Self {
-        Self::new(Vec::new(), false, 0)
-    }
-}
-
-impl Display for Row {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let last_index = self.values.len() - 1;
-
-        for (i, value) in self.values.iter().enumerate() {
-            let has_comma = i != last_index || self.has_trailing_comma();
-            write!(f, "{}{}", value, if has_comma {","} else {""})?;
-        }
-
-        write!(f, "{}", LINE_RETURN.repeat(self.get_trailing_newlines()))?;
-
-        Ok(())
-    }
-}
-
-#[derive(Debug)]
-pub struct  Section {
-    identifier: SectionIdentifier,
-    rows: Vec<Row>,
-    trailing_newlines: usize,
-}
-
-impl Section {
-    pub fn new(identifier: SectionIdentifier, rows: Vec<Row>, trailing_newlines: usize) -> Self {
-        Self { identifier, rows, trailing_newlines }
-    }
-
-    pub fn parse(input: &str) -> IResult<&str, Section, VerboseError<&str>> {
-        context(
-            "Section",
-            map(
-                tuple((
-                    terminated(
-                        SectionIdentifier::parse, 
-                        context(",", tag(",")),
-                    ),
-                    terminated(
-                        many1(Row::parse), 
-                        context(";", tag(";")),
-                    ),
-                    map(many0(line_ending), |v| v.len()),
-                )),
-                |(i, r, s)| Section::new(i, r, s),
-            ),
-        )(input)
-    }
-
-    pub fn get_identifier(&self) -> &SectionIdentifier {
-        &self.identifier
-    }
-
-    pub fn get_trailing_newlines(&self) -> usize {
-        self.trailing_newlines
-    }
-}
-
-impl<'a> IntoIterator for &'a Section {
-    type Item = &'a Row;
-    type IntoIter = std::slice::Iter<'a, Row>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.rows.iter()
-    }
-}
-
-impl Index<usize> for Section {
-    type Output = Row;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.rows[index]
-    }
-}
-
-impl IndexMut<usize> for Section {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.rows[index]
-    }
-}
-
-impl Display for Section {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{},", self.get_identifier().to_code())?;
-
-        for row in self.rows.iter() {
-            write!(f, "{}", row)?;
-        }
-
-        write!(f, ";")?;
-        write!(f, "{}", LINE_RETURN.repeat(self.get_trailing_newlines()))?;
-
-        Ok(())
-    }
-}
-
-#[derive(Debug)]
-pub struct Showfile {
-    headers: Vec<Header>,
-    sections: Vec<Section>,
-}
-
-impl Showfile {
-    pub fn new(headers: Vec<Header>, sections: Vec<Section>) -> Self {
-        Self { headers, sections }
-    }
-
-    pub fn parse(input: &str) -> IResult<&str, Showfile, VerboseError<&str>> {
-        context(
-            "Showfile",
-            map(
-                tuple((
-                    many1(Header::parse),
-                    many1(line_ending),
-                    many_till(Section::parse, eof),
-                )),
-                |(h, _, (s, _))| {
-                    Showfile::new(h, s)
-                },
-            )
-        )(input)
-    }
-
-    pub fn get_headers(&self) -> &[Header] {
-        &self.headers
-    }
-
-    pub fn get_sections(&self) -> &[Section] {
-        &self.sections
-    }
-}
-
-impl FromStr for Showfile {
-    type Err = String;
-
-    fn from_str(input: &str) -> Result<Showfile, String> {
-        let result = Self::parse(input).finish();
-        match result {
-            Ok((_, s)) => Ok(s),
-            Err(e) => Err(convert_error(input, e)),
-        }
-    }
-}
-
-impl Display for Showfile {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for header in self.get_headers() {
-            write!(f, "{}", header)?;
-        }
-
-        write!(f, "{}", LINE_RETURN)?;
-
-        for section in self.get_sections() {
-            write!(f, "{}", section)?;
-        }
-
-        Ok(())
-    }
-}
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, escaped},
+    character::complete::{hex_digit1, line_ending, none_of, char, not_line_ending, alphanumeric1},
+    combinator::{peek, eof, map, map_res, rest},
+    multi::{many0, many1, many_till},
+    sequence::{terminated, delimited, preceded, tuple},
+    error::{VerboseError, context},
+    IResult, number::streaming::double, Parser, Finish,
+};
+
+use crate::error::ParseError;
+use crate::span::Span;
+use nom::Offset;
+
+/// The line terminator observed while parsing, carried so that `Display`
+/// reproduces the input byte-for-byte. MagicQ writes `\r\n`; some toolchains
+/// re-emit `\n`, and the parser accepts either via nom's [`line_ending`], so
+/// the choice has to be recorded rather than assumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LineEnding {
+    /// `\r\n`, as written by MagicQ itself.
+    #[default]
+    CrLf,
+    /// A bare `\n`.
+    Lf,
+}
+
+impl LineEnding {
+    /// Classify a single terminator as produced by [`line_ending`].
+    fn detect(ending: &str) -> LineEnding {
+        if ending == "\r\n" {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Lf => "\n",
+        }
+    }
+}
+
+impl Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Reduce the run of line endings trailing a section's `;` to a count and the
+/// terminator used; an empty run (the file ended right after `;`) keeps the
+/// default.
+fn section_trailing(endings: Vec<&str>) -> (usize, LineEnding) {
+    let ending = endings.first().map_or(LineEnding::default(), |e| LineEnding::detect(e));
+    (endings.len(), ending)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Header {
+    value: String,
+    #[serde(default)]
+    line_ending: LineEnding,
+}
+
+impl Header {
+    pub fn new(value: &str, line_ending: LineEnding) -> Header {
+        Header { value: value.to_string(), line_ending }
+    }
+
+    pub fn parse(input: &str) -> IResult<&str, Header, VerboseError<&str>> {
+        context(
+            "Parsing Header",
+            map(
+                tuple((
+                    preceded(tag("\\ "), not_line_ending),
+                    line_ending,
+                )),
+                |(value, ending)| Header::new(value, LineEnding::detect(ending)),
+            ),
+        )(input)
+    }
+
+    /// The line ending that terminated this header line.
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+}
+
+impl Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\\ {}{}", self.value, self.line_ending)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Value {
+    Float(#[serde(with = "float_repr")] f64),
+    String(String),
+    Hex(u64, usize),
+}
+
+/// (De)serialize a [`Value::Float`] as a string rather than a JSON number.
+///
+/// MagicQ deliberately writes both `nan` and `-nan` (see [`Value::Display`]),
+/// but JSON has no NaN literal: a bare `f64` serializes to `null` and loses the
+/// sign, then fails to read back. Round-tripping through a string keeps the
+/// NaN sign — and therefore the byte-exact `Display` — intact.
+mod float_repr {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        let text = if value.is_nan() {
+            if value.is_sign_negative() { "-nan" } else { "nan" }.to_string()
+        } else {
+            value.to_string()
+        };
+        serializer.serialize_str(&text)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Ok(match text.as_str() {
+            "nan" => f64::NAN,
+            "-nan" => -f64::NAN,
+            other => other.parse().map_err(Error::custom)?,
+        })
+    }
+}
+
+impl Value {
+    fn parse_string(input: &str) -> IResult<&str, (Value, bool), VerboseError<&str>> {
+        context(
+            "String",
+            map(
+                tuple((
+                    alt((
+                        delimited(
+                            char('\"'),
+                            escaped(none_of("\""), '\\', char('\"')),
+                            char('\"'),
+                        ),
+                        map(tag("\"\""), |_| ""),
+                    )),
+                    alt((
+                        map(tag(","), |_| true),
+                        map(peek(tag(";")), |_| false),
+                        map(peek(line_ending), |_| false),
+                    )),
+                )),
+                |(s, c)| (Value::String(s.to_string()), c),
+            )
+        )(input)
+    }
+
+    fn parse_float(input: &str) -> IResult<&str, (Value, bool), VerboseError<&str>> {
+        context(
+            "Float",
+            map(
+                tuple((
+                    alt((
+                        double,
+                        map(tag("nan"), |_| f64::NAN),
+                        map(tag("-nan"), |_| -f64::NAN),
+                    )),
+                    alt((
+                        map(tag(","), |_| true),
+                        map(peek(tag(";")), |_| false),
+                        map(peek(line_ending), |_| false),
+                    )),
+                )),
+                |(f, c)| (Value::Float(f), c)
+            ),
+        )(input)
+    }
+
+    fn parse_hex(input: &str) -> IResult<&str, (Value, bool), VerboseError<&str>> {
+        context(
+            "Hex",
+            map_res(
+                tuple((
+                    hex_digit1.and(peek(rest.map(|r: &str| input.len() - r.len()))),
+                    alt((
+                        map(tag(","), |_| true),
+                        map(peek(tag(";")), |_| false),
+                        map(peek(line_ending), |_| false),
+                    )),
+                )),
+                |((h, l), c)| {
+                    u64::from_str_radix(h, 16).map(|v| (Value::Hex(v, l), c))
+                },
+            ),
+        )(input)
+}
+
+    pub fn parse(input: &str) -> IResult<&str, (Value, bool), VerboseError<&str>> {
+        context(
+            "Field",
+            alt((Self::parse_string, Self::parse_hex, Self::parse_float)),
+        )(input)
+    }
+
+    /// Read this field as a floating-point number. Both [`Value::Float`] and
+    /// [`Value::Hex`] convert (a hex id widens to `f64`); a string is `None`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Float(f) => Some(*f),
+            Value::Hex(v, _) => Some(*v as f64),
+            Value::String(_) => None,
+        }
+    }
+
+    /// Read this field as an unsigned integer, i.e. a [`Value::Hex`] payload.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Hex(v, _) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Read this field as a string, i.e. a [`Value::String`].
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Float(fl) => {
+                // Dirty hack because MagicQ sometimes writes out both
+                // nan and -nan. Please don't ask why it needs -nan.
+                if fl.is_nan() {
+                    write!(f, "{}", if fl.is_sign_positive() {
+                        "nan"
+                    } else {
+                        "-nan"
+                    })
+                } else {
+                    write!(f, "{:.6}", fl)
+                }
+            },
+            Value::String(s) => write!(f, "\"{}\"", s),
+            Value::Hex(h, w) => {
+                // Dirty hack because MagicQ sometimes writes out hex values
+                // in both upper case and lower case and I don't know why.
+                // If this breaks add a test case and figure out what the new
+                // terrible hack is to keep it happy.
+                if *w == 16 { 
+                    write!(f, "{:0width$X}", h, width = w)
+                } else {
+                    write!(f, "{:0width$x}", h, width = w)
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SectionIdentifier {
+    Version,
+    Settings,
+    Head,
+    Fixture,
+    Palette,
+    Group,
+    FX,
+    Playback,
+    CueStack,
+    ExecutePage,
+    ExecuteItem,
+    Unknown(String)
+}
+
+impl SectionIdentifier {
+    pub fn from_code(s: &str) -> SectionIdentifier {
+        match s {
+            "V" => SectionIdentifier::Version,
+            "T" => SectionIdentifier::Settings,
+            "P" => SectionIdentifier::Head,
+            "L" => SectionIdentifier::Fixture,
+            "F" => SectionIdentifier::Palette,
+            "G" => SectionIdentifier::Group,
+            "W" => SectionIdentifier::FX,
+            "S" => SectionIdentifier::Playback,
+            "C" => SectionIdentifier::CueStack,
+            "M" => SectionIdentifier::ExecutePage,
+            "N" => SectionIdentifier::ExecuteItem,
+            //"r" => SectionIdentifier::Unknown("r"),
+            //"Q" => SectionIdentifier::Unknown("Q"),
+            //"R" => SectionIdentifier::Unknown("R"),
+            //"Z" => SectionIdentifier::Unknown("Z"),
+            //"J" => SectionIdentifier::Unknown("J"),
+            //"u" => SectionIdentifier::Unknown("u"),
+            //"H" => SectionIdentifier::Unknown("H"),
+            //"E1" => SectionIdentifier::Unknown("E1"),
+            //"Y" => SectionIdentifier::Unknown("Y"),
+            _ => SectionIdentifier::Unknown(s.to_string()),
+        }
+    }
+    
+    pub fn to_code(&self) -> &str {
+        match self {
+            SectionIdentifier::Version => "V",
+            SectionIdentifier::Settings => "T",
+            SectionIdentifier::Head => "P",
+            SectionIdentifier::Fixture => "L",
+            SectionIdentifier::Palette => "F",
+            SectionIdentifier::Group => "G",
+            SectionIdentifier::FX => "W",
+            SectionIdentifier::Playback => "S",
+            SectionIdentifier::CueStack => "C",
+            SectionIdentifier::ExecutePage => "M",
+            SectionIdentifier::ExecuteItem => "N",
+            //SectionIdentifier::Unknown("r") => "r",
+            //SectionIdentifier::Unknown("Q") => "Q",
+            //SectionIdentifier::Unknown("R") => "R",
+            //SectionIdentifier::Unknown("Z") => "Z",
+            //SectionIdentifier::Unknown("J") => "J",
+            //SectionIdentifier::Unknown("u") => "u",
+            //SectionIdentifier::Unknown("H") => "H",
+            //SectionIdentifier::Unknown("E1") => "E1",
+            //SectionIdentifier::Unknown("Y") => "Y",
+            SectionIdentifier::Unknown(s) => s,
+        }
+    }
+
+    fn parse(input: &str) -> IResult<&str, SectionIdentifier, VerboseError<&str>> {
+        context(
+            "Section Identifier",
+            map(
+                alphanumeric1,
+                SectionIdentifier::from_code,
+            )
+        )(input)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Row {
+    values: Vec<Value>,
+    trailing_comma: bool,
+    trailing_newlines: usize,
+    /// Line ending used after this row, preserved for a byte-exact round-trip.
+    #[serde(default)]
+    line_ending: LineEnding,
+    /// Byte range this row was parsed from; `0..0` when synthesized rather than
+    /// parsed. Not part of the on-disk form, so it is skipped on (de)serialize.
+    #[serde(skip)]
+    span: Span,
+}
+
+impl Row {
+    fn new(values: Vec<Value>, trailing_comma: bool, trailing_newlines: usize, line_ending: LineEnding) -> Self {
+        Self { values, trailing_comma, trailing_newlines, line_ending, span: Span::default() }
+    }
+
+    /// Build a row from a list of field values, defaulting to the common
+    /// layout of a single trailing newline and no trailing comma. Used by the
+    /// typed-section serializer in [`crate::data`].
+    pub fn from_values(values: Vec<Value>) -> Self {
+        Self::new(values, false, 1, LineEnding::default())
+    }
+
+    fn parse(input: &str) -> IResult<&str, Row, VerboseError<&str>> {
+        context(
+            "Row",
+            map(
+                tuple((
+                    many1(Value::parse),
+                    alt((
+                        map(many1(line_ending), |l| (l.len(), LineEnding::detect(l[0]))),
+                        map(peek(tag(";")), |_| (0, LineEnding::default())),
+                    )),
+                )),
+                |(r, (n, ending))| {
+                    let comma = r.last().map(|t| t.1).unwrap_or(false);
+                    let values = r.into_iter().map(|t| t.0).collect_vec();
+                    Row::new(values, comma, n, ending)
+                },
+            ),
+        )(input)
+    }
+
+    /// Parse a row, recording its absolute [`span`](Self::span) against `base`
+    /// — the original, full input every sub-slice points into.
+    fn parse_spanned<'a>(input: &'a str, base: &str) -> IResult<&'a str, Row, VerboseError<&'a str>> {
+        let (rest, mut row) = Self::parse(input)?;
+        row.span = Span::new(base.offset(input), base.offset(rest));
+        Ok((rest, row))
+    }
+
+    /// Byte range this row was parsed from. See [`crate::span`].
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn get_values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Borrow column `index`, or `None` when the row is shorter than that.
+    ///
+    /// The non-panicking counterpart to the [`Index`] impl, used by the
+    /// read-only query layer in [`crate::query`] where a row may legitimately
+    /// be missing a column.
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        self.values.get(index)
+    }
+
+    pub fn has_trailing_comma(&self) -> bool {
+        self.trailing_comma
+    }
+
+    pub fn get_trailing_newlines(&self) -> usize {
+        self.trailing_newlines
+    }
+}
+
+impl<'a> IntoIterator for &'a Row {
+    type Item = &'a Value;
+    type IntoIter = std::slice::Iter<'a, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl Index<usize> for Row {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.values[index]
+    }
+}
+
+impl IndexMut<usize> for Row {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.values[index]
+    }
+}
+
+impl Default for Row {
+    fn default() -> Self {
+        Self::new(Vec::new(), false, 0, LineEnding::default())
+    }
+}
+
+impl Display for Row {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `--from json` accepts arbitrary external input, so an empty row is
+        // possible; `len() - 1` would underflow. The loop is then a no-op and
+        // `last_index` goes unused.
+        let last_index = self.values.len().saturating_sub(1);
+
+        for (i, value) in self.values.iter().enumerate() {
+            let has_comma = i != last_index || self.has_trailing_comma();
+            write!(f, "{}{}", value, if has_comma {","} else {""})?;
+        }
+
+        write!(f, "{}", self.line_ending.as_str().repeat(self.get_trailing_newlines()))?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct  Section {
+    identifier: SectionIdentifier,
+    rows: Vec<Row>,
+    trailing_newlines: usize,
+    /// Line ending used after this section's closing `;`, preserved for a
+    /// byte-exact round-trip.
+    #[serde(default)]
+    line_ending: LineEnding,
+    /// Byte range this section was parsed from; `0..0` when synthesized. Not
+    /// part of the on-disk form, so it is skipped on (de)serialize.
+    #[serde(skip)]
+    span: Span,
+}
+
+impl Section {
+    pub fn new(identifier: SectionIdentifier, rows: Vec<Row>, trailing_newlines: usize) -> Self {
+        Self::with_line_ending(identifier, rows, trailing_newlines, LineEnding::default())
+    }
+
+    fn with_line_ending(
+        identifier: SectionIdentifier,
+        rows: Vec<Row>,
+        trailing_newlines: usize,
+        line_ending: LineEnding,
+    ) -> Self {
+        Self { identifier, rows, trailing_newlines, line_ending, span: Span::default() }
+    }
+
+    pub fn parse(input: &str) -> IResult<&str, Section, VerboseError<&str>> {
+        context(
+            "Section",
+            map(
+                tuple((
+                    terminated(
+                        SectionIdentifier::parse,
+                        context(",", tag(",")),
+                    ),
+                    terminated(
+                        many1(Row::parse),
+                        context(";", tag(";")),
+                    ),
+                    map(many0(line_ending), section_trailing),
+                )),
+                |(i, r, (s, ending))| Section::with_line_ending(i, r, s, ending),
+            ),
+        )(input)
+    }
+
+    /// Parse a section — and its rows — recording absolute
+    /// [`span`](Self::span)s against `base`, the original full input.
+    fn parse_spanned<'a>(input: &'a str, base: &str) -> IResult<&'a str, Section, VerboseError<&'a str>> {
+        let (rest, mut section) = context(
+            "Section",
+            map(
+                tuple((
+                    terminated(
+                        SectionIdentifier::parse,
+                        context(",", tag(",")),
+                    ),
+                    terminated(
+                        many1(|i| Row::parse_spanned(i, base)),
+                        context(";", tag(";")),
+                    ),
+                    map(many0(line_ending), section_trailing),
+                )),
+                |(i, r, (s, ending))| Section::with_line_ending(i, r, s, ending),
+            ),
+        )(input)?;
+        section.span = Span::new(base.offset(input), base.offset(rest));
+        Ok((rest, section))
+    }
+
+    /// Byte range this section was parsed from. See [`crate::span`].
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn get_identifier(&self) -> &SectionIdentifier {
+        &self.identifier
+    }
+
+    pub fn get_rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    pub fn get_trailing_newlines(&self) -> usize {
+        self.trailing_newlines
+    }
+}
+
+impl<'a> IntoIterator for &'a Section {
+    type Item = &'a Row;
+    type IntoIter = std::slice::Iter<'a, Row>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.iter()
+    }
+}
+
+impl Index<usize> for Section {
+    type Output = Row;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.rows[index]
+    }
+}
+
+impl IndexMut<usize> for Section {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.rows[index]
+    }
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},", self.get_identifier().to_code())?;
+
+        for row in self.rows.iter() {
+            write!(f, "{}", row)?;
+        }
+
+        write!(f, ";")?;
+        write!(f, "{}", self.line_ending.as_str().repeat(self.get_trailing_newlines()))?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Showfile {
+    headers: Vec<Header>,
+    sections: Vec<Section>,
+}
+
+impl Showfile {
+    pub fn new(headers: Vec<Header>, sections: Vec<Section>) -> Self {
+        Self { headers, sections }
+    }
+
+    pub fn parse(input: &str) -> IResult<&str, Showfile, VerboseError<&str>> {
+        context(
+            "Showfile",
+            map(
+                tuple((
+                    many1(Header::parse),
+                    many1(line_ending),
+                    // `input` is the full buffer every sub-slice points into, so
+                    // offsets taken against it are absolute source positions.
+                    many_till(|i| Section::parse_spanned(i, input), eof),
+                )),
+                |(h, _, (s, _))| {
+                    Showfile::new(h, s)
+                },
+            )
+        )(input)
+    }
+
+    /// Parse a showfile, rendering any failure as a located [`ParseError`]
+    /// rather than a raw nom context dump.
+    pub fn parse_verbose(input: &str) -> Result<Showfile, ParseError> {
+        match Self::parse(input).finish() {
+            Ok((_, s)) => Ok(s),
+            Err(e) => Err(ParseError::from_verbose(input, e)),
+        }
+    }
+
+    pub fn get_headers(&self) -> &[Header] {
+        &self.headers
+    }
+
+    pub fn get_sections(&self) -> &[Section] {
+        &self.sections
+    }
+}
+
+impl FromStr for Showfile {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Showfile, ParseError> {
+        Self::parse_verbose(input)
+    }
+}
+
+impl Display for Showfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for header in self.get_headers() {
+            write!(f, "{}", header)?;
+        }
+
+        // The blank line separating the preamble from the sections uses the
+        // same terminator as the headers above it.
+        let separator = self.headers.first().map_or(LineEnding::default(), Header::line_ending);
+        write!(f, "{}", separator)?;
+
+        for section in self.get_sections() {
+            write!(f, "{}", section)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHOW: &str = "\\ MagicQ\r\n\\ Auto\r\n\r\nV,007d,\"x\",01090307,0000,0002,;\r\nC,0001,\"Default\",0,0;\r\n";
+
+    #[test]
+    fn display_round_trips_byte_for_byte() {
+        let show = Showfile::from_str(SHOW).expect("parse");
+        assert_eq!(show.to_string(), SHOW);
+    }
+
+    #[test]
+    fn nan_survives_a_json_round_trip() {
+        // JSON has no NaN literal; `-nan` must keep its sign through the string
+        // representation so `Display` still emits `-nan`.
+        let show = Showfile::from_str("\\ h\r\n\r\nV,nan,-nan,;\r\n").expect("parse");
+        let json = serde_json::to_string(&show).expect("to json");
+        let back: Showfile = serde_json::from_str(&json).expect("from json");
+        assert_eq!(back.to_string(), show.to_string());
+    }
+
+    #[test]
+    fn empty_row_displays_without_panicking() {
+        let row = Row::from_values(vec![]);
+        assert_eq!(row.to_string(), LineEnding::default().as_str());
+    }
+}
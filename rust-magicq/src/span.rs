@@ -0,0 +1,133 @@
+//! Byte-offset span tracking and a lazily-queried source map.
+//!
+//! The parser records, on every [`Row`](crate::showfile::Row) and
+//! [`Section`](crate::showfile::Section), the byte range it was cut from. Those
+//! offsets are cheap to carry (two `usize`s) and don't touch the serialized or
+//! `Display` form, so they exist purely for tooling — turning "this row" into a
+//! line/column range an editor can highlight.
+//!
+//! Offsets resolve to 1-based line/column through [`SourceMap`], which — like
+//! `proc-macro2`'s fallback source map — precomputes the line starts once and
+//! answers queries with a binary search rather than rescanning the input per
+//! lookup.
+
+/// A half-open byte range `start..end` into the original showfile text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+}
+
+/// A 1-based line and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Resolves byte offsets into the input to 1-based line/column positions.
+///
+/// Build one per input and reuse it; the line-start table is computed up front.
+pub struct SourceMap<'a> {
+    input: &'a str,
+    /// Byte offset of the start of each line; `line_starts[0]` is always 0.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn new(input: &'a str) -> SourceMap<'a> {
+        let line_starts = std::iter::once(0)
+            .chain(input.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        SourceMap { input, line_starts }
+    }
+
+    /// Resolve a byte offset to a 1-based line and column.
+    ///
+    /// Columns are counted in bytes, matching the offsets the parser records;
+    /// an offset past the end clamps to the final line.
+    pub fn locate(&self, offset: usize) -> Location {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(i) => i,
+            Err(i) => i - 1,
+        };
+        Location {
+            line: line + 1,
+            column: offset - self.line_starts[line] + 1,
+        }
+    }
+
+    /// The inverse of [`locate`](Self::locate): a byte offset for a 1-based
+    /// line/column, clamped into the input. Used by tooling that starts from an
+    /// editor position rather than a parser offset.
+    pub fn offset(&self, location: Location) -> usize {
+        let line = location.line.saturating_sub(1).min(self.line_starts.len() - 1);
+        (self.line_starts[line] + location.column.saturating_sub(1)).min(self.input.len())
+    }
+
+    /// The source line containing `offset`, without its line ending.
+    pub fn line_text(&self, offset: usize) -> &'a str {
+        let start = self.input[..offset.min(self.input.len())]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let end = self.input[start..]
+            .find('\n')
+            .map(|i| start + i)
+            .unwrap_or(self.input.len());
+        self.input[start..end].trim_end_matches('\r')
+    }
+
+    /// Resolve a [`Span`] to its start and end locations.
+    pub fn span(&self, span: Span) -> (Location, Location) {
+        (self.locate(span.start), self.locate(span.end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_offsets_as_line_and_column() {
+        let input = "abc\ndef\r\nghi";
+        let map = SourceMap::new(input);
+
+        assert_eq!(map.locate(0), Location { line: 1, column: 1 });
+        // 'd' is the first byte of line 2.
+        assert_eq!(map.locate(4), Location { line: 2, column: 1 });
+        // 'g' is the first byte of line 3, after the `\r\n`.
+        assert_eq!(map.locate(9), Location { line: 3, column: 1 });
+    }
+
+    #[test]
+    fn locate_and_offset_round_trip() {
+        let input = "abc\ndef\nghi";
+        let map = SourceMap::new(input);
+        for offset in 0..=input.len() {
+            let location = map.locate(offset);
+            assert_eq!(map.offset(location), offset);
+        }
+    }
+
+    #[test]
+    fn line_text_strips_the_carriage_return() {
+        let input = "abc\r\ndef\r\n";
+        let map = SourceMap::new(input);
+        assert_eq!(map.line_text(1), "abc");
+        assert_eq!(map.line_text(6), "def");
+    }
+
+    #[test]
+    fn past_the_end_clamps_to_the_last_line() {
+        let input = "abc\ndef";
+        let map = SourceMap::new(input);
+        assert_eq!(map.locate(input.len()).line, 2);
+    }
+}
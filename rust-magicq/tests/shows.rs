@@ -1,8 +1,15 @@
-use testsgenerator::generate_tests;
-use magicq::{showfile_parser, showfile_writer};
-use nom::{error::convert_error, Finish};
+// These imports are consumed by the bodies `generate_tests!` expands to, one
+// per `.shw` in the corpus; when a corpus directory is absent the macro expands
+// to nothing and leaves them unused.
+#[allow(unused_imports)]
+use std::str::FromStr;
+#[allow(unused_imports)]
+use magicq::showfile::Showfile;
+#[allow(unused_imports)]
 use pretty_assertions::assert_eq;
 
+use testsgenerator::generate_tests;
+
 // See testsgenerator/src/lib.rs
 generate_tests!("../events");
 generate_tests!("../show");
\ No newline at end of file